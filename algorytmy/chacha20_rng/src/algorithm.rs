@@ -0,0 +1,122 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{awc, bbs, lcg, park_miller};
+
+/*
+Rejestr generatorów dostępnych w binarkach rng i chacha20_rng (--algorithm).
+
+Każdy algorytm ma nazwę używaną w CLI i w polu "algorithm" wyniku JSON oraz
+domyślne bits_per_value (naturalna szerokość wartości generatora, tak jak
+w odpowiednim module Pythona). Wszystkie korzystają z tej samej ekstrakcji
+bitów (int_to_bits), obsługi msb_first i koperty JSON {"bits", "time"}.
+
+Lista: chacha20, pcg32, splitmix64, xoshiro256**, lcg, park-miller, awc, bbs,
+system, mt19937, mt19937-64 — kolejne generatory są dopisywane tutaj w miarę implementacji.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    #[default]
+    ChaCha20,
+    Pcg32,
+    SplitMix64,
+    Xoshiro256StarStar,
+    Lcg,
+    ParkMiller,
+    Awc,
+    Bbs,
+    System,
+    Mt19937,
+    Mt19937_64,
+}
+
+impl Algorithm {
+    // Wszystkie zarejestrowane algorytmy, w kolejności z pomocy CLI
+    pub const ALL: &'static [Algorithm] = &[
+        Algorithm::ChaCha20,
+        Algorithm::Pcg32,
+        Algorithm::SplitMix64,
+        Algorithm::Xoshiro256StarStar,
+        Algorithm::Lcg,
+        Algorithm::ParkMiller,
+        Algorithm::Awc,
+        Algorithm::Bbs,
+        Algorithm::System,
+        Algorithm::Mt19937,
+        Algorithm::Mt19937_64,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::ChaCha20 => "chacha20",
+            Algorithm::Pcg32 => "pcg32",
+            Algorithm::SplitMix64 => "splitmix64",
+            Algorithm::Xoshiro256StarStar => "xoshiro256**",
+            Algorithm::Lcg => "lcg",
+            Algorithm::ParkMiller => "park-miller",
+            Algorithm::Awc => "awc",
+            Algorithm::Bbs => "bbs",
+            Algorithm::System => "system",
+            Algorithm::Mt19937 => "mt19937",
+            Algorithm::Mt19937_64 => "mt19937-64",
+        }
+    }
+
+    /*
+    Domyślne bits_per_value: naturalna szerokość wartości generatora. Dla LCG
+    zależy od modułu (m.bit_length()), a dla AWC od bazy; tutaj dla wartości
+    domyślnych, a CLI liczy ją dla podanego --m / --base
+    (lcg::default_bits_per_value, awc::default_bits_per_value).
+    */
+    pub fn default_bits_per_value(&self) -> usize {
+        match self {
+            Algorithm::ChaCha20 | Algorithm::Pcg32 | Algorithm::System | Algorithm::Mt19937 => 32,
+            Algorithm::SplitMix64 | Algorithm::Xoshiro256StarStar | Algorithm::Mt19937_64 => 64,
+            Algorithm::Lcg => lcg::default_bits_per_value(lcg::DEFAULT_M),
+            Algorithm::ParkMiller => park_miller::DEFAULT_BITS_PER_VALUE,
+            Algorithm::Awc => awc::default_bits_per_value(awc::DEFAULT_BASE),
+            Algorithm::Bbs => bbs::DEFAULT_BITS_PER_VALUE,
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "chacha20" | "chacha" => Ok(Algorithm::ChaCha20),
+            "pcg32" | "pcg" => Ok(Algorithm::Pcg32),
+            "splitmix64" | "splitmix" => Ok(Algorithm::SplitMix64),
+            // "**" bywa kłopotliwe w powłoce, stąd nazwy zastępcze
+            "xoshiro256**" | "xoshiro256starstar" | "xoshiro256ss" | "xoshiro256" | "xoshiro" => {
+                Ok(Algorithm::Xoshiro256StarStar)
+            }
+            "lcg" => Ok(Algorithm::Lcg),
+            "park-miller" | "park_miller" | "parkmiller" | "minstd" => Ok(Algorithm::ParkMiller),
+            "awc" | "awcg" => Ok(Algorithm::Awc),
+            "bbs" | "blum-blum-shub" | "blumblumshub" => Ok(Algorithm::Bbs),
+            "system" | "systemrng" | "os" | "urandom" => Ok(Algorithm::System),
+            // PythonRNG.py to random.Random, czyli MT19937
+            "mt19937" | "mt" | "mersenne-twister" | "python" | "pythonrng" => {
+                Ok(Algorithm::Mt19937)
+            }
+            "mt19937-64" | "mt19937_64" | "mt64" => Ok(Algorithm::Mt19937_64),
+            _ => {
+                let names: Vec<&str> = Algorithm::ALL.iter().map(Algorithm::name).collect();
+                Err(format!(
+                    "nieznany algorytm: '{}' (dostępne: {})",
+                    text,
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
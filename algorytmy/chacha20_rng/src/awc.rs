@@ -0,0 +1,309 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::lcg::bit_length;
+use crate::stream::{stream_values, BitSink};
+
+/*
+AWC / SWB — generatory Marsaglii–Zamana z opóźnieniami r > s, natywny
+odpowiednik AWCG.py.
+
+    AWC (add-with-carry):       x_n = x_{n-r} + x_{n-s} + c       (mod base)
+    SWB (subtract-with-borrow): x_n = x_{n-s} - x_{n-r} - c       (mod base)
+
+c to przeniesienie (pożyczka): 1, jeśli suma osiągnęła base (różnica
+była ujemna), w przeciwnym razie 0.
+
+Typ publiczny:
+    Awc::new(seed, r, s, base, variant)   -- stan z prostego seeda jak w Pythonie
+    Awc::from_state(state, r, s, base, variant)
+                                          -- jawny stan (jak seed-sekwencja w AWCG.py)
+    Awc::next_value()                     -- kolejna wartość (0 ..= base - 1)
+    Awc::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                          -- strumieniowo do BitSink (stała pamięć)
+
+Tablica opóźnień to bufor cykliczny długości r: x_{n-r} leży dokładnie na
+pozycji, którą nadpisuje x_n, a x_{n-s} jest s pozycji wcześniej.
+
+Seedowanie jest identyczne z _simple_seed_state z AWCG.py: LCG glibc
+(a = 1103515245, c = 12345, m = 2^31) startujący z seed & 0x7fffffff (0 -> 1)
+wypełnia r wartości x mod base. Dzięki temu wariant AWC daje bit w bit to samo
+co awcg_bit_stream(seed, nBits, r, s, base, bitsPerValue, msbFirst).
+Python ma tylko AWC; SWB korzysta z tego samego seedowania.
+
+Funkcja publiczna:
+    awc_bit_stream(nBits, bitsPerValue, msbFirst=true, seed, r=24, s=10,
+                   base=2^32, variant=Awc)
+        -> Result<(bity, czas), komunikat> — błąd dla złych r, s, base i bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie jak
+                                w AWCG.py: bit_length(base) - 1 dla potęgi dwójki,
+                                inaczej bit_length(base); > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed : u64               -- wartość początkowa prostego seeda
+    r, s : usize             -- opóźnienia, r > s > 0
+    base : u128              -- baza (moduł), 2 <= base <= 2^64
+    variant : AwcVariant     -- Awc (dodawanie z przeniesieniem) albo Swb
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach albo błąd parametrów.
+*/
+
+// Domyślne parametry (jak w AWCG.py i UniversalRNGAdapter)
+pub const DEFAULT_SEED: u64 = 1;
+pub const DEFAULT_R: usize = 24;
+pub const DEFAULT_S: usize = 10;
+pub const DEFAULT_BASE: u128 = 1 << 32;
+
+pub const MAX_BASE: u128 = 1 << 64;
+// Górna granica r, żeby literówka w --r nie kończyła się alokacją terabajtów
+pub const MAX_LAG: usize = 1 << 20;
+
+// Prosty LCG z _simple_seed_state (tylko do inicjalizacji stanu)
+const SEED_A: u64 = 1103515245;
+const SEED_C: u64 = 12345;
+const SEED_MASK: u64 = 0x7fffffff;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AwcVariant {
+    #[default]
+    AddWithCarry,
+    SubtractWithBorrow,
+}
+
+impl FromStr for AwcVariant {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "awc" | "add-with-carry" => Ok(AwcVariant::AddWithCarry),
+            "swb" | "subtract-with-borrow" => Ok(AwcVariant::SubtractWithBorrow),
+            _ => Err(format!(
+                "nieznany wariant AWC: '{}' (dostępne: awc, swb)",
+                text
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AwcVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AwcVariant::AddWithCarry => "awc",
+            AwcVariant::SubtractWithBorrow => "swb",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Awc {
+    // Bufor cykliczny x_{n-r} .. x_{n-1}
+    lags: Vec<u64>,
+    // Pozycja x_{n-r} (nadpisywana przez x_n) i x_{n-s}
+    position: usize,
+    short_position: usize,
+    carry: u64,
+    base: u128,
+    variant: AwcVariant,
+}
+
+// Domyślne bits_per_value jak w AWCG.py
+pub fn default_bits_per_value(base: u128) -> usize {
+    if base.is_power_of_two() {
+        bit_length(base) - 1
+    } else {
+        bit_length(base)
+    }
+}
+
+pub fn validate_parameters(r: usize, s: usize, base: u128) -> Result<(), String> {
+    if s == 0 || s >= r {
+        return Err(format!("wymagane r > s > 0 (podano r = {}, s = {})", r, s));
+    }
+    if r > MAX_LAG {
+        return Err(format!(
+            "opóźnienie r może wynosić najwyżej {} (podano {})",
+            MAX_LAG, r
+        ));
+    }
+    if base < 2 {
+        return Err(format!("baza musi wynosić co najmniej 2 (podano {})", base));
+    }
+    if base > MAX_BASE {
+        return Err(format!("baza może wynosić najwyżej 2^64 (podano {})", base));
+    }
+    Ok(())
+}
+
+// _simple_seed_state(seed, count, base) z AWCG.py
+pub fn simple_seed_state(seed: u64, count: usize, base: u128) -> Vec<u64> {
+    let mut x = seed & SEED_MASK;
+    if x == 0 {
+        x = 1;
+    }
+    (0..count)
+        .map(|_| {
+            x = (SEED_A * x + SEED_C) & SEED_MASK;
+            (x as u128 % base) as u64
+        })
+        .collect()
+}
+
+impl Awc {
+    pub fn new(
+        seed: u64,
+        r: usize,
+        s: usize,
+        base: u128,
+        variant: AwcVariant,
+    ) -> Result<Self, String> {
+        validate_parameters(r, s, base)?;
+        Ok(Self::with_lags(
+            simple_seed_state(seed, r, base),
+            s,
+            base,
+            variant,
+        ))
+    }
+
+    /*
+    Stan podany wprost, jak seed-sekwencja w AWCG.py: wartości są redukowane
+    modulo base, krótszy stan jest dopełniany simple_seed_state(1, ...), a
+    nadmiarowe wartości (poza pierwszymi r) odrzucane.
+    */
+    pub fn from_state(
+        state: &[u64],
+        r: usize,
+        s: usize,
+        base: u128,
+        variant: AwcVariant,
+    ) -> Result<Self, String> {
+        validate_parameters(r, s, base)?;
+        let mut lags: Vec<u64> = state
+            .iter()
+            .take(r)
+            .map(|&value| (value as u128 % base) as u64)
+            .collect();
+        if lags.len() < r {
+            lags.extend(simple_seed_state(1, r - lags.len(), base));
+        }
+        Ok(Self::with_lags(lags, s, base, variant))
+    }
+
+    fn with_lags(lags: Vec<u64>, s: usize, base: u128, variant: AwcVariant) -> Self {
+        let r = lags.len();
+        Awc {
+            lags,
+            position: 0,
+            short_position: r - s,
+            carry: 0,
+            base,
+            variant,
+        }
+    }
+
+    pub fn next_value(&mut self) -> u64 {
+        let x_r = self.lags[self.position] as u128;
+        let x_s = self.lags[self.short_position] as u128;
+        let carry = self.carry as u128;
+
+        // Suma mieści się w u128 także dla base = 2^64
+        let value = match self.variant {
+            AwcVariant::AddWithCarry => {
+                let sum = x_r + x_s + carry;
+                if sum >= self.base {
+                    self.carry = 1;
+                    sum - self.base
+                } else {
+                    self.carry = 0;
+                    sum
+                }
+            }
+            AwcVariant::SubtractWithBorrow => {
+                let subtrahend = x_r + carry;
+                if x_s < subtrahend {
+                    self.carry = 1;
+                    x_s + self.base - subtrahend
+                } else {
+                    self.carry = 0;
+                    x_s - subtrahend
+                }
+            }
+        } as u64;
+
+        self.lags[self.position] = value;
+        self.position += 1;
+        if self.position == self.lags.len() {
+            self.position = 0;
+        }
+        self.short_position += 1;
+        if self.short_position == self.lags.len() {
+            self.short_position = 0;
+        }
+        value
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(
+            n_bits,
+            bits_per_value,
+            msb_first,
+            || self.next_value(),
+            sink,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn awc_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u64,
+    r: usize,
+    s: usize,
+    base: u128,
+    variant: AwcVariant,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let mut generator = Awc::new(seed, r, s, base, variant)?;
+    let bpv = bits_per_value.unwrap_or(default_bits_per_value(base));
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
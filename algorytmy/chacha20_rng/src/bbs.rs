@@ -0,0 +1,202 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive};
+use std::io;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::primes::is_probable_prime;
+use crate::stream::{stream_values, BitSink};
+
+/*
+Blum-Blum-Shub (BBS) — natywny odpowiednik BlumBlumShub.py z modułem dowolnej
+wielkości (num_bigint::BigUint).
+
+    N = p * q,  x_0 = seed^2 mod N,  x_{i+1} = x_i^2 mod N
+
+Z każdego kroku pobierane jest bitsPerValue najmłodszych bitów x_{i+1}.
+
+Typ publiczny:
+    Bbs::new(p, q, seed)              -- sprawdza parametry (poniżej)
+    Bbs::next_value(bitsPerValue)     -- kolejny krok, najmłodsze bity stanu
+    Bbs::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                      -- strumieniowo do BitSink (stała pamięć)
+    max_bits_per_value(N)             -- floor(log2(log2 N))
+
+Warunki (BlumBlumShub.py sprawdza tylko p % 4 == q % 4 == 3):
+    - p, q > 3 są pierwsze (primes::is_probable_prime — Miller–Rabin) i różne,
+    - p ≡ q ≡ 3 (mod 4) (liczby pierwsze Bluma),
+    - gcd(seed, N) = 1 — Python po cichu szuka najbliższego względnie pierwszego
+      seeda, tutaj to błąd; odrzucany jest też seed, dla którego x_0 = 1
+      (punkt stały, np. seed = 1 albo N - 1),
+    - bitsPerValue <= floor(log2(log2 N)) — bezpieczna liczba bitów na krok
+      (Vazirani–Vazirani); dla domyślnych p = 383, q = 503 to 4.
+
+Dla poprawnych parametrów wynik jest bit w bit taki sam jak
+bbs_bit_stream(seed, nBits, p, q, bitsPerValue, msbFirst). Domyślne p i q są
+zabawkowe (okres rzędu tysięcy kroków); jako CSPRNG należy podać p i q po
+512 bitów lub więcej.
+
+Funkcja publiczna:
+    bbs_bit_stream(nBits, bitsPerValue=1, msbFirst=true, seed, p, q)
+        -> Result<(bity, czas), komunikat> — błąd dla złych p, q, seeda i bitsPerValue
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile najmłodszych bitów pobrać z każdego kroku
+                                (domyślnie 1, 1 ..= max_bits_per_value(N))
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed : BigUint           -- ziarno, gcd(seed, N) = 1
+    p, q : BigUint           -- różne liczby pierwsze Bluma
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach albo błąd parametrów.
+*/
+
+// Domyślne parametry jak w BlumBlumShub.py (seed z przykładu użycia)
+pub const DEFAULT_P: u64 = 383;
+pub const DEFAULT_Q: u64 = 503;
+pub const DEFAULT_SEED: u64 = 12345;
+pub const DEFAULT_BITS_PER_VALUE: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bbs {
+    modulus: BigUint,
+    state: BigUint,
+}
+
+// floor(log2(log2 N)) = floor(log2(floor(log2 N))), a floor(log2 N) = bits(N) - 1
+pub fn max_bits_per_value(modulus: &BigUint) -> usize {
+    let floor_log2 = modulus.bits().saturating_sub(1);
+    (u64::BITS - floor_log2.leading_zeros()).saturating_sub(1) as usize
+}
+
+// Sprawdza p i q, zwraca N = p * q
+pub fn validate_primes(p: &BigUint, q: &BigUint) -> Result<BigUint, String> {
+    for (name, prime) in [("p", p), ("q", q)] {
+        if *prime <= BigUint::from(3u32) || prime % 4u32 != BigUint::from(3u32) {
+            return Err(format!(
+                "{} = {} musi być liczbą pierwszą Bluma: {} > 3 i {} ≡ 3 (mod 4)",
+                name, prime, name, name
+            ));
+        }
+        if !is_probable_prime(prime) {
+            return Err(format!(
+                "{} = {} nie jest liczbą pierwszą (test Millera–Rabina)",
+                name, prime
+            ));
+        }
+    }
+    if p == q {
+        return Err("p i q muszą być różne".to_string());
+    }
+    Ok(p * q)
+}
+
+pub fn validate_seed(seed: &BigUint, modulus: &BigUint) -> Result<(), String> {
+    let divisor = seed.gcd(modulus);
+    if !divisor.is_one() {
+        return Err(format!(
+            "seed BBS musi być względnie pierwszy z N: gcd(seed, N) = {} (seed = {})",
+            divisor, seed
+        ));
+    }
+    if (seed * seed % modulus).is_one() {
+        return Err(format!(
+            "seed BBS = {} daje seed^2 ≡ 1 (mod N) — generator utknąłby w punkcie stałym",
+            seed
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_bits_per_value(bits_per_value: usize, modulus: &BigUint) -> Result<(), String> {
+    check_bits_per_value(bits_per_value)?;
+    let max = max_bits_per_value(modulus);
+    if bits_per_value > max {
+        return Err(format!(
+            "bits_per_value dla BBS może wynosić najwyżej floor(log2(log2 N)) = {} (podano {})",
+            max, bits_per_value
+        ));
+    }
+    Ok(())
+}
+
+impl Bbs {
+    pub fn new(p: &BigUint, q: &BigUint, seed: &BigUint) -> Result<Self, String> {
+        let modulus = validate_primes(p, q)?;
+        validate_seed(seed, &modulus)?;
+        let state = seed * seed % &modulus;
+        Ok(Bbs { modulus, state })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    pub fn max_bits_per_value(&self) -> usize {
+        max_bits_per_value(&self.modulus)
+    }
+
+    pub fn next_value(&mut self, bits_per_value: usize) -> u64 {
+        self.state = &self.state * &self.state % &self.modulus;
+        // bits_per_value <= log2(log2 N) jest zawsze dużo mniejsze od 64
+        let low = &self.state & ((BigUint::one() << bits_per_value) - 1u32);
+        low.to_u64().unwrap_or(u64::MAX)
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(
+            n_bits,
+            bits_per_value,
+            msb_first,
+            || self.next_value(bits_per_value),
+            sink,
+        )
+    }
+}
+
+pub fn bbs_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: &BigUint,
+    p: &BigUint,
+    q: &BigUint,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let mut generator = Bbs::new(p, q, seed)?;
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE);
+    validate_bits_per_value(bpv, generator.modulus())?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
@@ -0,0 +1,43 @@
+use chacha20_rng::cli;
+
+/*
+rng — jedna binarka dla wszystkich natywnych generatorów.
+
+Generator wybiera --algorithm (rejestr w algorithm.rs); pozostałe opcje, format
+wyjścia i koperta JSON {"bits": [...], "time": ...} są takie same jak
+w chacha20_rng, więc backend potrzebuje tylko jednego wywołania podprocesu:
+    rng --algorithm <nazwa> --seed <u64> --bits <n> [--bits-per-value <n>] [--lsb-first]
+
+Bez --algorithm działa jak chacha20_rng (także w formie pozycyjnej
+<seed> <n_bits> [bits_per_value] [msb_first]). Każdy JSON zawiera pole
+"algorithm" z nazwą generatora, którym powstał wynik.
+*/
+
+fn main() {
+    if let Err(message) = cli::run("rng", std::env::args().skip(1)) {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+/*
+Krótki przykład użycia z terminala:
+
+# ChaCha20 (domyślnie), pozycyjnie jak tester.py
+./rng 42 1000000
+
+# PCG32: initstate=42, seq=54, LSB-first
+./rng --algorithm pcg32 --seed 42 --seq 54 --bits 1000000 --lsb-first
+
+# SplitMix64, surowe bajty do PractRand
+./rng --algorithm splitmix64 --seed 42 --bits 1e11 --format raw | RNG_test stdin
+
+# Testy NIST na strumieniu generatora, bez wypisywania bitów (raport JSON)
+./rng test --algorithm pcg32 --seed 42 --tests monobit,runs,serial --bits 10000000
+
+# Złożoność liniowa: profil LFSR całego ciągu i każdej pozycji bitu (lanes)
+./rng linear-complexity --algorithm lcg --bits 100000
+
+# Wynik: JSON z bitami, czasem wykonania i parametrami generatora
+{"algorithm":"pcg32","bits":[0,1,1,1,...],"initstate":42,"seed":42,"seq":54,"time":0.001234}
+*/
@@ -0,0 +1,433 @@
+use std::borrow::Cow;
+use std::collections::TryReserveError;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+/*
+Ekstrakcja bitów z wartości — odpowiednik _int_to_bits z generatorów w Pythonie.
+
+    int_to_bits(value, bits, msb_first)  -> Vec<u8> z bitami 0/1
+    int_to_bits_into(value, bits, msb_first, out)
+                                         -> to samo, dopisywane do istniejącego bufora
+    bits_to_int(bits, msb_first)         -> u64 (operacja odwrotna)
+    check_bits_per_value(bits)           -> błąd dla szerokości 0 (jedyna niepoprawna)
+    bytes_to_bits_into(bytes, bits, msb_first, out)
+                                         -> `bits` najmłodszych bitów liczby zapisanej
+                                            big-endian w `bytes` (dowolna szerokość)
+
+Tryb ekstrakcji (Extraction) dla generatorów bajtowych (ChaCha20, system):
+    Truncate    -- "obcinanie per słowo": każda wartość zajmuje ceil(bpv / 8)
+                   bajtów, a nadmiarowe najstarsze bity są odrzucane
+                   (dotychczasowe zachowanie, domyślne)
+    Contiguous  -- "ciągły strumień bitów": wartości są kolejnymi bpv-bitowymi
+                   kawałkami strumienia, żaden bit nie jest marnowany
+
+Ciąg bitów do testów (BitSequence) to [u8] / Vec<u8> z bajtem na bit albo
+PackedBits z 64 bitami w słowie u64 — 1 bit pamięci na bit ciągu, dla podkomend
+test i linear-complexity, które trzymają cały ciąg w pamięci.
+
+ByteExtractor realizuje oba tryby dla dowolnego źródła bajtów (funkcja `fill`
+wypełniająca bufor kolejnymi bajtami strumienia), więc generatory bajtowe
+dzielą jedną ścieżkę ekstrakcji.
+
+Jak w Pythonie, wartość jest traktowana jako liczba o nieograniczonej
+szerokości: pozycje powyżej 63 w int_to_bits dają bity 0.
+*/
+
+pub fn int_to_bits(value: u64, bits: usize, msb_first: bool) -> Vec<u8> {
+    let mut result = Vec::with_capacity(bits);
+    int_to_bits_into(value, bits, msb_first, &mut result);
+    result
+}
+
+pub fn int_to_bits_into(value: u64, bits: usize, msb_first: bool, out: &mut Vec<u8>) {
+    let bit_at = |i: usize| -> u8 {
+        if i < 64 {
+            ((value >> i) & 1) as u8
+        } else {
+            0
+        }
+    };
+
+    if msb_first {
+        out.extend((0..bits).rev().map(bit_at));
+    } else {
+        out.extend((0..bits).map(bit_at));
+    }
+}
+
+pub fn bytes_to_bits_into(bytes: &[u8], bits: usize, msb_first: bool, out: &mut Vec<u8>) {
+    // Bit na pozycji i (licząc od najmłodszego) leży w bajcie liczonym od końca
+    let bit_at = |i: usize| -> u8 {
+        match bytes.len().checked_sub(1 + i / 8) {
+            Some(index) => (bytes[index] >> (i % 8)) & 1,
+            None => 0,
+        }
+    };
+
+    if msb_first {
+        out.extend((0..bits).rev().map(bit_at));
+    } else {
+        out.extend((0..bits).map(bit_at));
+    }
+}
+
+pub fn check_bits_per_value(bits_per_value: usize) -> Result<(), String> {
+    match bits_per_value {
+        0 => Err("bits_per_value musi być większe od 0".to_string()),
+        _ => Ok(()),
+    }
+}
+
+pub fn bits_to_int(bits: &[u8], msb_first: bool) -> u64 {
+    let mut value: u64 = 0;
+    if msb_first {
+        for &bit in bits {
+            value = (value << 1) | (bit & 1) as u64;
+        }
+    } else {
+        for &bit in bits.iter().rev() {
+            value = (value << 1) | (bit & 1) as u64;
+        }
+    }
+    value
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Extraction {
+    #[default]
+    Truncate,
+    Contiguous,
+}
+
+impl FromStr for Extraction {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "truncate" => Ok(Extraction::Truncate),
+            "contiguous" => Ok(Extraction::Contiguous),
+            _ => Err(format!(
+                "nieznany tryb ekstrakcji: '{}' (dostępne: truncate, contiguous)",
+                text
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Extraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Extraction::Truncate => "truncate",
+            Extraction::Contiguous => "contiguous",
+        };
+        f.write_str(name)
+    }
+}
+
+// Stan ekstrakcji bitów ze strumienia bajtów (wspólny dla generatorów bajtowych)
+#[derive(Debug, Clone, Default)]
+pub struct ByteExtractor {
+    // Bajty bieżącej wartości przy ekstrakcji bitów (dowolna szerokość)
+    value_bytes: Vec<u8>,
+    // Niewykorzystane bity ostatniego bajtu w trybie contiguous
+    spare: u8,
+    spare_bits: u32,
+}
+
+impl ByteExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /*
+    Kolejna wartość jako u64: ceil(bpv / 8) bajtów big-endian zamaskowane do
+    bpv bitów. Dla bpv > 64 zwracane są tylko 64 najmłodsze bity — pełną
+    wartość dowolnej szerokości daje next_value_bits.
+    */
+    pub fn next_value<F>(&mut self, mut fill: F, bits_per_value: usize) -> u64
+    where
+        F: FnMut(&mut [u8]),
+    {
+        let num_bytes = bits_per_value.div_ceil(8);
+        self.value_bytes.resize(num_bytes, 0);
+        fill(&mut self.value_bytes);
+
+        // Konwertuj bytes do u64 (big-endian), liczą się tylko 8 ostatnich bajtów
+        let mut val: u64 = 0;
+        for byte in &self.value_bytes[num_bytes.saturating_sub(8)..] {
+            val = (val << 8) | (*byte as u64);
+        }
+
+        // Maskuj do bitsPerValue
+        if bits_per_value < 64 {
+            val &= (1u64 << bits_per_value) - 1;
+        }
+        val
+    }
+
+    /*
+    Dopisuje do `out` bity kolejnej wartości: pobiera ceil(bpv / 8) bajtów
+    strumienia, traktuje je jako liczbę big-endian i bierze jej bpv
+    najmłodszych bitów (nadmiarowe najstarsze bity są odrzucane).
+    */
+    pub fn next_value_bits<F>(
+        &mut self,
+        mut fill: F,
+        bits_per_value: usize,
+        msb_first: bool,
+        out: &mut Vec<u8>,
+    ) where
+        F: FnMut(&mut [u8]),
+    {
+        let num_bytes = bits_per_value.div_ceil(8);
+        self.value_bytes.resize(num_bytes, 0);
+        fill(&mut self.value_bytes);
+        bytes_to_bits_into(&self.value_bytes, bits_per_value, msb_first, out);
+    }
+
+    /*
+    Wariant contiguous: bity kolejnej wartości to następne bpv bitów strumienia
+    (każdy bajt czytany od najstarszego bitu), bez odrzucania czegokolwiek.
+    Nadwyżka ostatniego bajtu czeka na następną wartość.
+    */
+    pub fn next_value_bits_contiguous<F>(
+        &mut self,
+        mut fill: F,
+        bits_per_value: usize,
+        msb_first: bool,
+        out: &mut Vec<u8>,
+    ) where
+        F: FnMut(&mut [u8]),
+    {
+        let start = out.len();
+        let mut needed = bits_per_value;
+
+        // Najpierw resztka bajtu z poprzedniej wartości
+        while needed > 0 && self.spare_bits > 0 {
+            self.spare_bits -= 1;
+            out.push((self.spare >> self.spare_bits) & 1);
+            needed -= 1;
+        }
+
+        // Pełne bajty
+        let full_bytes = needed / 8;
+        if full_bytes > 0 {
+            self.value_bytes.resize(full_bytes, 0);
+            fill(&mut self.value_bytes);
+            bytes_to_bits_into(&self.value_bytes, full_bytes * 8, true, out);
+            needed -= full_bytes * 8;
+        }
+
+        // Początek kolejnego bajtu; reszta zostaje na później
+        if needed > 0 {
+            let mut byte = [0u8; 1];
+            fill(&mut byte);
+            self.spare = byte[0];
+            self.spare_bits = 8;
+            while needed > 0 {
+                self.spare_bits -= 1;
+                out.push((self.spare >> self.spare_bits) & 1);
+                needed -= 1;
+            }
+        }
+
+        if !msb_first {
+            out[start..].reverse();
+        }
+    }
+
+    // Bity kolejnej wartości w wybranym trybie ekstrakcji
+    pub fn next_bits<F>(
+        &mut self,
+        fill: F,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+        out: &mut Vec<u8>,
+    ) where
+        F: FnMut(&mut [u8]),
+    {
+        match extraction {
+            Extraction::Truncate => self.next_value_bits(fill, bits_per_value, msb_first, out),
+            Extraction::Contiguous => {
+                self.next_value_bits_contiguous(fill, bits_per_value, msb_first, out)
+            }
+        }
+    }
+}
+
+// Ciąg bitów 0/1 z dostępem swobodnym (testy NIST i złożoność liniowa)
+pub trait BitSequence {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn bit(&self, index: usize) -> u8;
+
+    // Bity z zakresu jako bajty 0/1; [u8] oddaje je bez kopiowania
+    fn unpack(&self, range: Range<usize>) -> Cow<'_, [u8]>;
+}
+
+impl BitSequence for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn bit(&self, index: usize) -> u8 {
+        self[index]
+    }
+
+    fn unpack(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self[range])
+    }
+}
+
+impl BitSequence for Vec<u8> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn bit(&self, index: usize) -> u8 {
+        self[index]
+    }
+
+    fn unpack(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self[range])
+    }
+}
+
+impl<const N: usize> BitSequence for [u8; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn bit(&self, index: usize) -> u8 {
+        self[index]
+    }
+
+    fn unpack(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self[range])
+    }
+}
+
+// Kolejne fragmenty po `size` bitów (ostatni może być krótszy)
+pub fn bit_chunks<B>(bits: &B, size: usize) -> impl Iterator<Item = Cow<'_, [u8]>>
+where
+    B: BitSequence + ?Sized,
+{
+    let n = bits.len();
+    (0..n)
+        .step_by(size)
+        .map(move |start| bits.unpack(start..(start + size).min(n)))
+}
+
+// Bit i ciągu to bit i % 64 słowa i / 64
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedBits {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl PackedBits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(n_bits: usize) -> Self {
+        PackedBits {
+            words: Vec::with_capacity(n_bits.div_ceil(64)),
+            len: 0,
+        }
+    }
+
+    // Jak with_capacity, ale brak pamięci to błąd zamiast przerwania procesu
+    pub fn try_with_capacity(n_bits: usize) -> Result<Self, TryReserveError> {
+        let mut words = Vec::new();
+        words.try_reserve_exact(n_bits.div_ceil(64))?;
+        Ok(PackedBits { words, len: 0 })
+    }
+
+    pub fn push(&mut self, bit: u8) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        self.words[self.len / 64] |= ((bit & 1) as u64) << (self.len % 64);
+        self.len += 1;
+    }
+
+    pub fn extend_from_bits(&mut self, bits: &[u8]) {
+        self.words
+            .reserve((self.len + bits.len()).div_ceil(64) - self.words.len());
+        for &bit in bits {
+            self.push(bit);
+        }
+    }
+
+    // Po 8 bitów z każdego bajtu w kolejności msb_first (odwrotność output::pack_bits)
+    pub fn extend_from_bytes(&mut self, bytes: &[u8], msb_first: bool) {
+        self.words
+            .reserve((self.len + bytes.len() * 8).div_ceil(64) - self.words.len());
+        for &byte in bytes {
+            // Bit i ciągu to bit i % 64 słowa, więc bajt LSB-first trafia do słowa wprost
+            let byte = if msb_first { byte.reverse_bits() } else { byte };
+            if self.len.is_multiple_of(64) {
+                self.words.push(0);
+            }
+            let shift = self.len % 64;
+            self.words[self.len / 64] |= (byte as u64) << shift;
+            if shift > 56 {
+                self.words.push((byte as u64) >> (64 - shift));
+            }
+            self.len += 8;
+        }
+    }
+
+    // Skraca ciąg do `len` bitów (dłuższe `len` niczego nie zmienia)
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.words.truncate(len.div_ceil(64));
+        // push zakłada zera za końcem ciągu
+        if let Some(last) = self.words.last_mut() {
+            if !len.is_multiple_of(64) {
+                *last &= (1u64 << (len % 64)) - 1;
+            }
+        }
+        self.len = len;
+    }
+}
+
+impl BitSequence for PackedBits {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn bit(&self, index: usize) -> u8 {
+        assert!(
+            index < self.len,
+            "indeks bitu {} poza ciągiem ({})",
+            index,
+            self.len
+        );
+        ((self.words[index / 64] >> (index % 64)) & 1) as u8
+    }
+
+    fn unpack(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        assert!(
+            range.end <= self.len,
+            "zakres {:?} poza ciągiem ({})",
+            range,
+            self.len
+        );
+        Cow::Owned(
+            range
+                .map(|index| ((self.words[index / 64] >> (index % 64)) & 1) as u8)
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,344 @@
+use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use chacha20::ChaCha20;
+use rand_core::{CryptoRng, Error, RngCore, SeedableRng};
+use std::io;
+use std::num::NonZeroU32;
+use std::time::Instant;
+
+use crate::bits::{check_bits_per_value, ByteExtractor, Extraction};
+use crate::splitmix64::SplitMix64;
+use crate::stream::{stream_bits, BitSink};
+
+/*
+ChaCha20 CSPRNG — Algorytm szyfrowania strumienia do generacji liczb losowych.
+
+Typ publiczny:
+    ChaCha20Generator::new(key, nonce)      -- generator z jawnym kluczem i nonce
+    ChaCha20Generator::from_seed_u64(seed)  -- klucz z seeda przez KDF (derive_key)
+    ChaCha20Generator::fill_keystream(buf)  -- kolejne bajty strumienia klucza
+    ChaCha20Generator::set_block_pos(block) -- przejście do bloku o danym liczniku
+    ChaCha20Generator::stream_bits(nBits, bitsPerValue, msbFirst, extraction, sink)
+                                            -- strumieniowo do BitSink (stała pamięć)
+    keystream_bytes(nBits, bitsPerValue, extraction)
+                                            -- bajty strumienia klucza zużyte przez stream_bits
+                                               (błąd dla bitsPerValue = 0)
+
+Tryb ekstrakcji (bits::Extraction) decyduje, czy każda wartość zajmuje pełne
+bajty strumienia klucza (truncate — nadmiarowe bity są odrzucane, np. dla
+bpv = 12 ginie 4 z 16 bitów), czy strumień jest cięty na bpv-bitowe kawałki
+bez strat (contiguous). chacha20_bit_stream zawsze używa trybu truncate.
+
+ChaCha20Generator implementuje rand_core::RngCore i SeedableRng, więc działa
+z rand::distributions, tasowaniem itp. Słowa next_u32/next_u64 to kolejne
+bajty strumienia klucza czytane little-endian, a from_seed(klucz) używa nonce
+zerowego — dla tego samego klucza daje to ten sam strumień co
+rand_chacha::ChaCha20Rng (do 2^32 bloków). seed_from_u64 używa KDF derive_key,
+czyli zgadza się z opcją --seed w CLI.
+
+Licznik bloku przy 96-bitowym nonce ma 32 bity, więc strumień klucza kończy
+się po KEYSTREAM_LIMIT = (2^32 - 1) * 64 bajtach (~2^41 bitów). try_fill_bytes
+zwraca wtedy błąd o kodzie KEYSTREAM_EXHAUSTED, a pozostałe metody panikują. CLI
+odrzuca --bits, dla których keystream_bytes przekracza KEYSTREAM_LIMIT.
+
+Funkcja publiczna:
+    chacha20_bit_stream(nBits, bitsPerValue=32, msbFirst=true, key, nonce)
+        -> Result<(bity, czas), komunikat> — błąd dla bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 32, > 0);
+                                szerokość jest dowolna (np. 128, 256, 512 = cały blok)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    key : [u8; 32]           -- 256-bitowy klucz ChaCha20
+    nonce : [u8; 12]         -- 96-bitowy nonce ChaCha20
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach.
+*/
+
+pub const KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 12;
+
+/*
+KDF seed -> klucz: stan SplitMix64 inicjalizowany seedem, cztery kolejne
+wyjścia (tak jak w SplitMix64.py) zapisane little-endian dają 4 x 64 = 256 bitów.
+Ten sam seed zawsze daje ten sam klucz, a sąsiednie seedy dają niezależne klucze.
+*/
+pub fn derive_key(seed: u64) -> [u8; KEY_LEN] {
+    let mut splitmix = SplitMix64::new(seed);
+    let mut key = [0u8; KEY_LEN];
+    for chunk in key.chunks_exact_mut(8) {
+        chunk.copy_from_slice(&splitmix.next_u64().to_le_bytes());
+    }
+    key
+}
+
+// Długość strumienia klucza: bloki o licznikach 0..=2^32 - 2 po 64 bajty
+pub const KEYSTREAM_LIMIT: u64 = u32::MAX as u64 * 64;
+
+// Kod rand_core::Error zwracany przez try_fill_bytes po końcu strumienia klucza
+pub const KEYSTREAM_EXHAUSTED: u32 = Error::CUSTOM_START;
+
+/*
+Bajty strumienia klucza na n_bits bitów: stream_bits bierze ceil(n_bits / bpv)
+pełnych wartości, w trybie truncate po ceil(bpv / 8) bajtów każda, w trybie
+contiguous łącznie ceil(wartości * bpv / 8) bajtów.
+*/
+pub fn keystream_bytes(
+    n_bits: u64,
+    bits_per_value: usize,
+    extraction: Extraction,
+) -> Result<u128, String> {
+    check_bits_per_value(bits_per_value)?;
+    let values = (n_bits as u128).div_ceil(bits_per_value as u128);
+    Ok(match extraction {
+        Extraction::Truncate => values * bits_per_value.div_ceil(8) as u128,
+        Extraction::Contiguous => (values * bits_per_value as u128).div_ceil(8),
+    })
+}
+
+// Rozmiar bufora strumienia klucza (wielokrotność 64-bajtowego bloku ChaCha20)
+const KEYSTREAM_BUFFER: usize = 1024;
+
+// Strumień klucza ChaCha20 buforowany paczkami po KEYSTREAM_BUFFER bajtów
+struct Keystream {
+    cipher: ChaCha20,
+    buffer: [u8; KEYSTREAM_BUFFER],
+    position: usize,
+    // Liczba ważnych bajtów bufora (tuż przed końcem strumienia mniej niż KEYSTREAM_BUFFER)
+    filled: usize,
+}
+
+impl Keystream {
+    // Bajty, które można jeszcze pobrać: reszta bufora i niewygenerowane bloki
+    fn remaining(&self) -> u64 {
+        (self.filled - self.position) as u64 + KEYSTREAM_LIMIT - self.cipher.current_pos::<u64>()
+    }
+
+    fn fill(&mut self, mut dest: &mut [u8]) {
+        // Strumień klucza jest generowany paczkami, bo pojedyncze wywołania
+        // apply_keystream dla kilku bajtów są kosztowne
+        while !dest.is_empty() {
+            if self.position == self.filled {
+                // Ostatnia paczka przed końcem licznika jest krótsza
+                let len = (KEYSTREAM_LIMIT - self.cipher.current_pos::<u64>())
+                    .min(KEYSTREAM_BUFFER as u64) as usize;
+                assert!(
+                    len > 0,
+                    "strumień klucza ChaCha20 wyczerpany (32-bitowy licznik bloku, {} bajtów)",
+                    KEYSTREAM_LIMIT
+                );
+                self.buffer[..len].fill(0);
+                self.cipher.apply_keystream(&mut self.buffer[..len]);
+                self.position = 0;
+                self.filled = len;
+            }
+            let take = (self.filled - self.position).min(dest.len());
+            dest[..take].copy_from_slice(&self.buffer[self.position..self.position + take]);
+            self.position += take;
+            dest = &mut dest[take..];
+        }
+    }
+
+    // fill bez paniki: za długie żądanie nie zużywa strumienia
+    fn try_fill(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        if dest.len() as u64 > self.remaining() {
+            return Err(Error::from(
+                NonZeroU32::new(KEYSTREAM_EXHAUSTED).expect("kod błędu jest niezerowy"),
+            ));
+        }
+        self.fill(dest);
+        Ok(())
+    }
+}
+
+pub struct ChaCha20Generator {
+    keystream: Keystream,
+    key: [u8; KEY_LEN],
+    nonce: [u8; NONCE_LEN],
+    extractor: ByteExtractor,
+}
+
+impl ChaCha20Generator {
+    pub fn new(key: [u8; KEY_LEN], nonce: [u8; NONCE_LEN]) -> Self {
+        let cipher = ChaCha20::new(key.as_ref().into(), nonce.as_ref().into());
+        ChaCha20Generator {
+            keystream: Keystream {
+                cipher,
+                buffer: [0u8; KEYSTREAM_BUFFER],
+                position: 0,
+                filled: 0,
+            },
+            key,
+            nonce,
+            extractor: ByteExtractor::new(),
+        }
+    }
+
+    pub fn from_seed_u64(seed: u64) -> Self {
+        Self::new(derive_key(seed), [0u8; NONCE_LEN])
+    }
+
+    pub fn key(&self) -> &[u8; KEY_LEN] {
+        &self.key
+    }
+
+    pub fn nonce(&self) -> &[u8; NONCE_LEN] {
+        &self.nonce
+    }
+
+    // Nadpisuje bufor kolejnymi bajtami strumienia klucza
+    pub fn fill_keystream(&mut self, dest: &mut [u8]) {
+        self.keystream.fill(dest);
+    }
+
+    // Następne bajty strumienia zaczną się od bloku o liczniku `block`
+    // (bufor strumienia i resztka bajtu trybu contiguous są porzucane)
+    pub fn set_block_pos(&mut self, block: u32) {
+        self.keystream.cipher.seek(block as u64 * 64);
+        self.keystream.position = 0;
+        self.keystream.filled = 0;
+        self.extractor = ByteExtractor::new();
+    }
+
+    // Kolejna wartość jako u64 (bits::ByteExtractor::next_value)
+    pub fn next_value(&mut self, bits_per_value: usize) -> u64 {
+        let keystream = &mut self.keystream;
+        self.extractor
+            .next_value(|dest| keystream.fill(dest), bits_per_value)
+    }
+
+    // Bity kolejnej wartości w trybie truncate (ByteExtractor::next_value_bits)
+    pub fn next_value_bits(&mut self, bits_per_value: usize, msb_first: bool, out: &mut Vec<u8>) {
+        let keystream = &mut self.keystream;
+        self.extractor
+            .next_value_bits(|dest| keystream.fill(dest), bits_per_value, msb_first, out);
+    }
+
+    // Bity kolejnej wartości w trybie contiguous (ByteExtractor::next_value_bits_contiguous)
+    pub fn next_value_bits_contiguous(
+        &mut self,
+        bits_per_value: usize,
+        msb_first: bool,
+        out: &mut Vec<u8>,
+    ) {
+        let keystream = &mut self.keystream;
+        self.extractor.next_value_bits_contiguous(
+            |dest| keystream.fill(dest),
+            bits_per_value,
+            msb_first,
+            out,
+        );
+    }
+
+    // Bity kolejnej wartości w wybranym trybie ekstrakcji
+    pub fn next_bits(
+        &mut self,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+        out: &mut Vec<u8>,
+    ) {
+        let keystream = &mut self.keystream;
+        self.extractor.next_bits(
+            |dest| keystream.fill(dest),
+            bits_per_value,
+            msb_first,
+            extraction,
+            out,
+        );
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits (`out` jest odbiorcą stream_bits)
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+    ) -> io::Result<()> {
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, extraction, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        check_bits_per_value(bits_per_value)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+        stream_bits(
+            n_bits,
+            |batch| self.next_bits(bits_per_value, msb_first, extraction, batch),
+            sink,
+        )
+    }
+}
+
+impl RngCore for ChaCha20Generator {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_keystream(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_keystream(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.fill_keystream(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.keystream.try_fill(dest)
+    }
+}
+
+impl SeedableRng for ChaCha20Generator {
+    type Seed = [u8; KEY_LEN];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(seed, [0u8; NONCE_LEN])
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed_u64(state)
+    }
+}
+
+// Strumień klucza ChaCha20 jest kryptograficznie bezpieczny
+impl CryptoRng for ChaCha20Generator {}
+
+pub fn chacha20_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    key: &[u8; KEY_LEN],
+    nonce: &[u8; NONCE_LEN],
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let bpv = bits_per_value.unwrap_or(32);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut generator = ChaCha20Generator::new(*key, *nonce);
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first, Extraction::Truncate)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
@@ -0,0 +1,1360 @@
+/*
+Wspólna warstwa CLI binarek rng i chacha20_rng.
+
+    parse_args(args)            -- parsowanie argumentów do Command
+    generate(options, sink)     -- uruchamia wybrany generator (--algorithm)
+    run(program, args)          -- całość: argumenty -> generator -> wyjście
+                                   (albo raport dla podkomend test, analyze
+                                   i linear-complexity)
+
+Dwa równoważne sposoby wywołania:
+
+    Pozycyjnie (kontrakt tester.py / Xoshiro256):
+        rng <seed> <n_bits> [bits_per_value] [msb_first]
+
+    Nazwanymi opcjami:
+        rng --algorithm pcg32 --seed 42 --bits 1000000 --bits-per-value 32 --lsb-first
+
+Podkomenda test generuje bity w pamięci procesu i od razu przepuszcza je przez
+testy NIST (nist.rs), wypisując raport JSON zamiast bitów:
+        rng test --tests monobit,runs,serial --bits 10000000 --seed 42 --alpha 0.01
+
+Podkomenda analyze robi to samo dla bitów z pliku (input.rs: raw, hex, base64,
+ascii01 albo JSON {"bits": [...]}), z tym samym formatem raportu:
+        rng analyze trng.bin --tests monobit,runs
+
+Podkomenda linear-complexity liczy profil złożoności liniowej (lfsr.rs) ciągu
+z generatora i każdej pozycji bitu w wartości:
+        rng linear-complexity --algorithm lcg --bits 100000
+
+Generator wybiera --algorithm (rejestr w algorithm.rs, domyślnie chacha20); opcje
+dotyczące tylko wybranych algorytmów (--key, --nonce dla chacha20, --extraction
+dla chacha20 i system, --seq dla pcg32, --jump i --long-jump dla xoshiro256**,
+--a, --c, --m dla lcg, --multiplier dla park-miller, --r, --s, --base, --variant
+dla awc, --p, --q dla bbs, --init dla mt19937 i mt19937-64) podane przy innym algorytmie są błędem.
+Tak samo opcje formatu: --width dla hex, base64 i ascii01, --json-bits dla json.
+
+Opcje nazwane mają postać "--opcja wartość" albo "--opcja=wartość".
+Każdy parametr można podać tylko raz (pozycyjnie albo opcją), a niepoprawna
+wartość kończy program błędem zamiast cichego powrotu do wartości domyślnych.
+*/
+
+use num_bigint::BigUint;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::algorithm::Algorithm;
+use crate::awc::{self, Awc, AwcVariant};
+use crate::bbs::{self, Bbs};
+use crate::bits::{BitSequence, Extraction, PackedBits};
+use crate::chacha::{
+    derive_key, keystream_bytes, ChaCha20Generator, KEYSTREAM_LIMIT, KEY_LEN, NONCE_LEN,
+};
+use crate::input;
+use crate::lcg::{self, Lcg};
+use crate::lfsr;
+use crate::mt19937::{self, Mt19937, Mt19937_64, MtInit};
+use crate::nist::{self, NistTest};
+use crate::output::{create_sink, to_hex, Format, JsonBits};
+use crate::park_miller::{self, ParkMiller};
+use crate::pcg32::{self, Pcg32};
+use crate::splitmix64::{self, SplitMix64};
+use crate::stream::BitSink;
+use crate::system::SystemRng;
+use crate::xoshiro256::{self, Xoshiro256StarStar};
+
+pub const DEFAULT_N_BITS: u64 = 200;
+// Domyślna długość ciągu dla podkomendy test (wystarcza każdemu testowi NIST)
+pub const DEFAULT_TEST_BITS: u64 = 1_000_000;
+
+// Domyślna długość ciągu dla podkomendy linear-complexity (Berlekamp–Massey to O(n^2 / 64))
+pub const DEFAULT_LFSR_BITS: u64 = 100_000;
+
+// Testy uruchamiane przez podkomendy test i analyze bez --tests: wszystkie 15
+pub const DEFAULT_TESTS: &[NistTest] = NistTest::ALL;
+
+// Tekst pomocy z nazwą uruchomionej binarki (rng albo chacha20_rng)
+pub fn usage(program: &str) -> String {
+    format!(
+        "\
+Użycie:
+    {program} <seed> <n_bits> [bits_per_value] [msb_first]
+    {program} [opcje]
+    {program} test [opcje] [--tests <lista>] [--alpha <a>]
+    {program} analyze <plik> [--format <f>] [--lsb-first] [--bits <n>] [--tests <lista>] [--alpha <a>]
+    {program} linear-complexity [opcje]
+
+Opcje:
+    --algorithm <nazwa>       generator: chacha20 (domyślnie), pcg32, splitmix64,
+                              xoshiro256** (także jako xoshiro256), lcg,
+                              park-miller (także jako minstd), awc, bbs,
+                              system (entropia systemu: getrandom(2) albo
+                              /dev/urandom; seed jest ignorowany),
+                              mt19937 (także jako python, zgodny z modułem
+                              random), mt19937-64
+    --seed <u64>              seed: dla chacha20 KDF wyprowadza z niego klucz 256-bit,
+                              dla pcg32 to initstate, dla splitmix64 stan początkowy
+                              (dla obu domyślnie 1), dla xoshiro256** seed SplitMix64,
+                              z którego powstaje stan (domyślnie 0), dla lcg
+                              wartość początkowa x0 (domyślnie 123456789), dla
+                              park-miller wartość początkowa, seed mod (2^31 - 1)
+                              różny od 0 (domyślnie 1), dla awc seed prostego LCG
+                              wypełniającego tablicę opóźnień (domyślnie 1), dla
+                              bbs ziarno, gcd(seed, N) = 1 (domyślnie 12345),
+                              dla mt19937 i mt19937-64 seed inicjalizacji
+                              (domyślnie 5489); system ignoruje seed
+                              (ostrzeżenie w warnings)
+    --seq <u64>               numer strumienia PCG32 (domyślnie 1)
+    --jump <n>                xoshiro256**: n skoków o 2^128 kroków przed generacją
+                              (niezależne podciągi dla równoległych strumieni)
+    --long-jump <n>           xoshiro256**: n skoków o 2^192 kroków (przed --jump)
+    --a <n>, --c <n>, --m <n> parametry LCG x = (a*x + c) mod m, 2 <= m <= 2^64;
+                              można pisać potęgi jako 2^31 albo 2**31
+                              (domyślnie glibc: a=1103515245, c=12345, m=2^31);
+                              niespełnione warunki Hulla–Dobella trafiają do
+                              pola warnings w JSON (dla innych formatów na stderr)
+    --multiplier <a>          mnożnik Park–Millera: 16807 (domyślnie, 1988),
+                              48271 albo 69621 (1993)
+    --r <n>, --s <n>          opóźnienia AWC/SWB, r > s > 0 (domyślnie 24 i 10)
+    --base <n>                baza AWC/SWB, 2 <= base <= 2^64, np. 2^32 (domyślnie)
+    --variant <awc|swb>       awc: x = x[n-r] + x[n-s] + c (domyślnie),
+                              swb: x = x[n-s] - x[n-r] - c (subtract-with-borrow)
+    --p <n>, --q <n>          liczby pierwsze Bluma dla bbs (≡ 3 mod 4, różne),
+                              dziesiętnie albo szesnastkowo z prefiksem 0x, dowolnej
+                              wielkości (domyślnie zabawkowe 383 i 503)
+    --init <init_by_array|init_genrand>
+                              inicjalizacja Mersenne Twistera: init_by_array
+                              z kluczem ze słów seeda (domyślnie dla mt19937,
+                              jak random.Random(seed)) albo init_genrand(seed)
+                              (domyślnie dla mt19937-64, jak std::mt19937_64;
+                              dla mt19937 seed <= 2^32 - 1)
+    --key <64 znaki hex>      jawny klucz ChaCha20 (wyklucza --seed)
+    --nonce <24 znaki hex>    jawny nonce ChaCha20 (domyślnie same zera)
+    --bits <n>                liczba bitów do wygenerowania (domyślnie 200);
+                              przyjmuje też zapis naukowy, np. 1e11; dla chacha20
+                              najwyżej ~2^41 (strumień klucza jednego nonce:
+                              (2^32 - 1) * 64 bajtów, mniej przy truncate z bpv
+                              niepodzielnym przez 8)
+    --bits-per-value <n>      ile bitów pobrać z każdej wartości (domyślnie 32,
+                              dla splitmix64, xoshiro256** i mt19937-64 64, dla lcg
+                              m.bit_length(), dla park-miller 31, dla awc
+                              bit_length(base) - 1 dla potęgi dwójki, inaczej
+                              bit_length(base), dla bbs 1, najwyżej
+                              floor(log2(log2 N)); > 0;
+                              dowolna szerokość, np. 128, 256 albo 512 = cały blok)
+    --extraction <tryb>       chacha20 i system: truncate (domyślnie): każda
+                              wartość z pełnych bajtów,
+                              nadmiarowe bity odrzucane; contiguous: strumień cięty
+                              na kawałki po bits_per_value bez marnowania bitów
+    --lsb-first               kolejność bitów LSB-first (domyślnie MSB-first)
+    --msb-first               kolejność bitów MSB-first
+    --format <f>              format wyjścia: json (domyślnie), raw, hex, base64, ascii01
+    --width <n>               szerokość linii dla hex/base64/ascii01 (0 = bez łamania;
+                              domyślnie hex 64, base64 76, ascii01 80)
+    --json-bits <array|base64>
+                              zapis bitów w JSON: tablica 0/1 (domyślnie) albo
+                              spakowane bajty w base64 (pole bits_base64)
+    --output <ścieżka>        zapisz wynik do pliku zamiast na stdout
+    -h, --help                wypisz tę pomoc
+
+Podkomenda test (bity nie trafiają na wyjście, tylko do testów NIST; wynik to
+raport JSON z pozycją na test: statistic, p_value, passed; --format, --width
+i --json-bits nie dotyczą tej podkomendy):
+    --tests <lista>           testy po przecinku, z prefiksem nist_ albo bez, np.
+                              monobit,runs,serial; all = wszystkie 15 (domyślnie)
+    --alpha <a>               poziom istotności, 0 < a < 1 (domyślnie 0.01):
+                              test przechodzi, gdy każda jego p-wartość >= a
+    --bits <n>                długość testowanego ciągu (domyślnie 1000000)
+
+Pamięć podkomendy test: ciąg zajmuje n/8 bajtów (bit na bit), a test dft
+dodatkowo do 1 GiB buforów FFT niezależnie od n (widmo liczone warstwami).
+Gdy n ma duży czynnik pierwszy, dft liczy dłużej (n pierwsze rzędu 2^27:
+minuty, rzędu 2^30: godziny); dla n > 2^30 zwraca błąd. Pozostałe testy
+zużywają pomijalnie mało pamięci.
+
+Podkomenda analyze (te same testy i raport dla bitów z pliku; - = stdin):
+    --format <f>              format wejścia: json ({{\"bits\": [...]}}, bits_base64
+                              albo generated_bits z API), raw, hex, base64,
+                              ascii01 (domyślnie rozpoznawany po zawartości;
+                              base64 tylko jawnie)
+    --lsb-first               kolejność bitów w bajcie dla raw, hex i base64
+                              (domyślnie MSB-first; JSON z bit_order ma pierwszeństwo)
+    --bits <n>                testuj tylko pierwsze n bitów (np. bez dopełnienia
+                              ostatniego bajtu; domyślnie wszystkie)
+    --tests <lista>, --alpha <a>, --output <ścieżka>
+                              jak dla podkomendy test
+
+Podkomenda linear-complexity (złożoność liniowa Berlekampa–Masseya: raport JSON
+z profilem długości LFSR całego ciągu i każdej pozycji bitu w wartości, lanes;
+predictable = LFSR z pierwszych 2L bitów przewiduje resztę; opcje generatora
+jak wyżej, bez --format, --width i --json-bits):
+    --bits <n>                długość ciągu (domyślnie 100000; koszt O(n^2 / 64))
+"
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub algorithm: Algorithm,
+    pub seed: Option<u64>,
+    pub seq: Option<u64>,
+    pub jump: u64,
+    pub long_jump: u64,
+    pub lcg_a: Option<u128>,
+    pub lcg_c: Option<u128>,
+    pub lcg_m: Option<u128>,
+    pub pm_multiplier: Option<u64>,
+    pub awc_r: Option<usize>,
+    pub awc_s: Option<usize>,
+    pub awc_base: Option<u128>,
+    pub awc_variant: AwcVariant,
+    pub bbs_p: Option<BigUint>,
+    pub bbs_q: Option<BigUint>,
+    pub mt_init: Option<MtInit>,
+    pub key: Option<[u8; KEY_LEN]>,
+    pub nonce: [u8; NONCE_LEN],
+    pub n_bits: u64,
+    pub bits_per_value: usize,
+    pub extraction: Extraction,
+    pub msb_first: bool,
+    pub format: Format,
+    pub width: usize,
+    pub json_bits: JsonBits,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Generate(Box<Options>),
+    // Podkomenda test: generator z `options`, testy i poziom istotności
+    Test {
+        options: Box<Options>,
+        tests: Vec<NistTest>,
+        alpha: f64,
+    },
+    Analyze(Box<AnalyzeOptions>),
+    // Podkomenda linear-complexity: profil LFSR bitów z generatora `options`
+    LinearComplexity(Box<Options>),
+    Help,
+}
+
+// Co robi wywołanie z opcjami generatora: wypisuje bity albo raport podkomendy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Generate,
+    Test,
+    LinearComplexity,
+}
+
+impl Mode {
+    // Opis do komunikatu o opcji, która dotyczy tylko tego trybu
+    fn description(self) -> &'static str {
+        match self {
+            Mode::Generate => "generowania bitów (raporty są zawsze w JSON)",
+            Mode::Test => "podkomendy test",
+            Mode::LinearComplexity => "podkomendy linear-complexity",
+        }
+    }
+}
+
+// Podkomenda analyze: plik z bitami i ustawienia raportu
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeOptions {
+    pub input: PathBuf,
+    pub format: Option<Format>,
+    pub msb_first: bool,
+    pub n_bits: Option<u64>,
+    pub tests: Vec<NistTest>,
+    pub alpha: f64,
+    pub output: Option<PathBuf>,
+}
+
+// Wartości zebrane z linii poleceń, zanim zostaną uzupełnione domyślnymi
+#[derive(Default)]
+struct Collected {
+    algorithm: Option<Algorithm>,
+    seed: Option<u64>,
+    seq: Option<u64>,
+    jump: Option<u64>,
+    long_jump: Option<u64>,
+    lcg_a: Option<u128>,
+    lcg_c: Option<u128>,
+    lcg_m: Option<u128>,
+    pm_multiplier: Option<u64>,
+    awc_r: Option<usize>,
+    awc_s: Option<usize>,
+    awc_base: Option<u128>,
+    awc_variant: Option<AwcVariant>,
+    bbs_p: Option<BigUint>,
+    bbs_q: Option<BigUint>,
+    mt_init: Option<MtInit>,
+    key: Option<[u8; KEY_LEN]>,
+    nonce: Option<[u8; NONCE_LEN]>,
+    n_bits: Option<u64>,
+    bits_per_value: Option<usize>,
+    extraction: Option<Extraction>,
+    msb_first: Option<bool>,
+    format: Option<Format>,
+    width: Option<usize>,
+    json_bits: Option<JsonBits>,
+    output: Option<PathBuf>,
+    tests: Option<Vec<NistTest>>,
+    alpha: Option<f64>,
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, name: &str) -> Result<(), String> {
+    if slot.is_some() {
+        return Err(format!("parametr {} podano więcej niż raz", name));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str, name: &str) -> Result<T, String> {
+    text.trim()
+        .parse::<T>()
+        .map_err(|_| format!("niepoprawna wartość {}: '{}'", name, text))
+}
+
+// Liczba bitów: liczba całkowita albo zapis naukowy o wartości całkowitej (1e11, 2.5e6)
+fn parse_count(text: &str, name: &str) -> Result<u64, String> {
+    let text = text.trim();
+    if let Ok(count) = text.parse::<u64>() {
+        return Ok(count);
+    }
+
+    let invalid = || format!("niepoprawna wartość {}: '{}'", name, text);
+    if !text.contains(['e', 'E']) {
+        return Err(invalid());
+    }
+    let value = text.parse::<f64>().map_err(|_| invalid())?;
+    // 2^53: powyżej f64 nie reprezentuje już dokładnie każdej liczby całkowitej
+    if !value.is_finite() || value < 0.0 || value.fract() != 0.0 || value > 9.007_199_254_740_992e15
+    {
+        return Err(invalid());
+    }
+    Ok(value as u64)
+}
+
+// Parametr LCG / AWC: liczba całkowita albo potęga zapisana jako 2^k lub 2**k
+fn parse_parameter(text: &str, name: &str) -> Result<u128, String> {
+    let text = text.trim();
+    let invalid = || format!("niepoprawna wartość {}: '{}'", name, text);
+    match text.split_once("**").or_else(|| text.split_once('^')) {
+        Some((base, exponent)) => {
+            let base: u128 = parse_number(base, name)?;
+            let exponent: u32 = parse_number(exponent, name)?;
+            base.checked_pow(exponent).ok_or_else(invalid)
+        }
+        None => text.parse::<u128>().map_err(|_| invalid()),
+    }
+}
+
+// Liczba dowolnej wielkości (p, q dla BBS): dziesiętnie albo 0x... szesnastkowo
+fn parse_big(text: &str, name: &str) -> Result<BigUint, String> {
+    let text = text.trim();
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(text.as_bytes(), 10),
+    };
+    parsed.ok_or_else(|| format!("niepoprawna wartość {}: '{}'", name, text))
+}
+
+fn parse_bool(text: &str, name: &str) -> Result<bool, String> {
+    match text.trim().to_lowercase().as_str() {
+        "true" | "1" | "msb" => Ok(true),
+        "false" | "0" | "lsb" => Ok(false),
+        _ => Err(format!(
+            "niepoprawna wartość {}: '{}' (oczekiwano true/false)",
+            name, text
+        )),
+    }
+}
+
+// Lista testów: nazwy po przecinku (bez powtórzeń) albo "all"
+fn parse_tests(text: &str) -> Result<Vec<NistTest>, String> {
+    if text.trim().eq_ignore_ascii_case("all") {
+        return Ok(NistTest::ALL.to_vec());
+    }
+    let mut tests = Vec::new();
+    for name in text.split(',').filter(|name| !name.trim().is_empty()) {
+        let test: NistTest = name.parse()?;
+        if !tests.contains(&test) {
+            tests.push(test);
+        }
+    }
+    if tests.is_empty() {
+        return Err("--tests wymaga co najmniej jednego testu".to_string());
+    }
+    Ok(tests)
+}
+
+fn parse_alpha(text: &str) -> Result<f64, String> {
+    let alpha: f64 = parse_number(text, "--alpha")?;
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err(format!(
+            "--alpha musi spełniać 0 < alpha < 1 (podano {})",
+            text
+        ));
+    }
+    Ok(alpha)
+}
+
+pub fn parse_hex<const N: usize>(text: &str, what: &str) -> Result<[u8; N], String> {
+    let text = text.trim();
+    if text.len() != 2 * N {
+        return Err(format!(
+            "{} musi mieć dokładnie {} znaków hex (podano {})",
+            what,
+            2 * N,
+            text.len()
+        ));
+    }
+
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        let pair = text.get(2 * i..2 * i + 2).unwrap_or("");
+        *byte = u8::from_str_radix(pair, 16)
+            .map_err(|_| format!("{} zawiera niepoprawne znaki hex: {}", what, text))?;
+    }
+    Ok(out)
+}
+
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut collected = Collected::default();
+    let mut positional: Vec<String> = Vec::new();
+
+    let mut args = args.into_iter().peekable();
+    // Podkomenda musi być pierwszym argumentem
+    if args.next_if(|arg| arg == "analyze").is_some() {
+        return parse_analyze_args(args);
+    }
+    let mode = if args.next_if(|arg| arg == "test").is_some() {
+        Mode::Test
+    } else if args.next_if(|arg| arg == "linear-complexity").is_some() {
+        Mode::LinearComplexity
+    } else {
+        Mode::Generate
+    };
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(Command::Help);
+        }
+
+        if !arg.starts_with("--") {
+            positional.push(arg);
+            continue;
+        }
+
+        // Rozdziel "--opcja=wartość"
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+
+        // Flagi bez wartości
+        if name == "--lsb-first" || name == "--msb-first" {
+            if inline_value.is_some() {
+                return Err(format!("opcja {} nie przyjmuje wartości", name));
+            }
+            set_once(&mut collected.msb_first, name == "--msb-first", "msb_first")?;
+            continue;
+        }
+
+        let mut value = || -> Result<String, String> {
+            match &inline_value {
+                Some(value) => Ok(value.clone()),
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("brak wartości dla {}", name)),
+            }
+        };
+
+        match name.as_str() {
+            "--algorithm" => set_once(&mut collected.algorithm, value()?.parse()?, "algorithm")?,
+            "--seed" => set_once(
+                &mut collected.seed,
+                parse_number(&value()?, "--seed")?,
+                "seed",
+            )?,
+            "--seq" => set_once(&mut collected.seq, parse_number(&value()?, "--seq")?, "seq")?,
+            "--jump" => set_once(
+                &mut collected.jump,
+                parse_number(&value()?, "--jump")?,
+                "jump",
+            )?,
+            "--long-jump" => set_once(
+                &mut collected.long_jump,
+                parse_number(&value()?, "--long-jump")?,
+                "long-jump",
+            )?,
+            "--a" => set_once(
+                &mut collected.lcg_a,
+                parse_parameter(&value()?, "--a")?,
+                "a",
+            )?,
+            "--c" => set_once(
+                &mut collected.lcg_c,
+                parse_parameter(&value()?, "--c")?,
+                "c",
+            )?,
+            "--m" => set_once(
+                &mut collected.lcg_m,
+                parse_parameter(&value()?, "--m")?,
+                "m",
+            )?,
+            "--multiplier" => set_once(
+                &mut collected.pm_multiplier,
+                parse_number(&value()?, "--multiplier")?,
+                "multiplier",
+            )?,
+            "--r" => set_once(&mut collected.awc_r, parse_number(&value()?, "--r")?, "r")?,
+            "--s" => set_once(&mut collected.awc_s, parse_number(&value()?, "--s")?, "s")?,
+            "--base" => set_once(
+                &mut collected.awc_base,
+                parse_parameter(&value()?, "--base")?,
+                "base",
+            )?,
+            "--variant" => set_once(&mut collected.awc_variant, value()?.parse()?, "variant")?,
+            "--p" => set_once(&mut collected.bbs_p, parse_big(&value()?, "--p")?, "p")?,
+            "--q" => set_once(&mut collected.bbs_q, parse_big(&value()?, "--q")?, "q")?,
+            "--init" => set_once(&mut collected.mt_init, value()?.parse()?, "init")?,
+            "--key" => set_once(&mut collected.key, parse_hex(&value()?, "--key")?, "key")?,
+            "--nonce" => set_once(
+                &mut collected.nonce,
+                parse_hex(&value()?, "--nonce")?,
+                "nonce",
+            )?,
+            "--bits" => set_once(
+                &mut collected.n_bits,
+                parse_count(&value()?, "--bits")?,
+                "n_bits",
+            )?,
+            "--bits-per-value" => set_once(
+                &mut collected.bits_per_value,
+                parse_number(&value()?, "--bits-per-value")?,
+                "bits_per_value",
+            )?,
+            "--extraction" => set_once(&mut collected.extraction, value()?.parse()?, "extraction")?,
+            "--format" => set_once(&mut collected.format, value()?.parse()?, "format")?,
+            "--width" => set_once(
+                &mut collected.width,
+                parse_number(&value()?, "--width")?,
+                "width",
+            )?,
+            "--json-bits" => set_once(&mut collected.json_bits, value()?.parse()?, "json-bits")?,
+            "--output" => set_once(&mut collected.output, PathBuf::from(value()?), "output")?,
+            "--tests" => set_once(&mut collected.tests, parse_tests(&value()?)?, "tests")?,
+            "--alpha" => set_once(&mut collected.alpha, parse_alpha(&value()?)?, "alpha")?,
+            _ => return Err(format!("nieznana opcja: {}", name)),
+        }
+    }
+
+    // Argumenty pozycyjne: <seed> <n_bits> [bits_per_value] [msb_first]
+    if positional.len() > 4 {
+        return Err(format!(
+            "za dużo argumentów pozycyjnych ({}), oczekiwano najwyżej 4",
+            positional.len()
+        ));
+    }
+    for (index, arg) in positional.iter().enumerate() {
+        match index {
+            0 => set_once(&mut collected.seed, parse_number(arg, "seed")?, "seed")?,
+            1 => set_once(&mut collected.n_bits, parse_count(arg, "n_bits")?, "n_bits")?,
+            2 => set_once(
+                &mut collected.bits_per_value,
+                parse_number(arg, "bits_per_value")?,
+                "bits_per_value",
+            )?,
+            _ => set_once(
+                &mut collected.msb_first,
+                parse_bool(arg, "msb_first")?,
+                "msb_first",
+            )?,
+        }
+    }
+
+    if collected.bits_per_value == Some(0) {
+        return Err("bits_per_value musi być większe od 0".to_string());
+    }
+
+    if collected.seed.is_some() && collected.key.is_some() {
+        return Err("--seed i --key wykluczają się nawzajem".to_string());
+    }
+
+    // Opcje formatu wyjścia dotyczą tylko generowania bitów, --tests i --alpha tylko testów
+    let mode_options = [
+        ("--format", collected.format.is_some(), Mode::Generate),
+        ("--width", collected.width.is_some(), Mode::Generate),
+        ("--json-bits", collected.json_bits.is_some(), Mode::Generate),
+        ("--tests", collected.tests.is_some(), Mode::Test),
+        ("--alpha", collected.alpha.is_some(), Mode::Test),
+    ];
+    if let Some((name, _, owner)) = mode_options
+        .iter()
+        .find(|&&(_, given, owner)| given && owner != mode)
+    {
+        return Err(format!(
+            "opcja {} dotyczy tylko {}",
+            name,
+            owner.description()
+        ));
+    }
+
+    let format = collected.format.unwrap_or(Format::Json);
+    // Opcje, które mają sens tylko dla wybranych formatów wyjścia
+    let format_options: &[(&str, bool, &[Format])] = &[
+        (
+            "--width",
+            collected.width.is_some(),
+            &[Format::Hex, Format::Base64, Format::Ascii01],
+        ),
+        (
+            "--json-bits",
+            collected.json_bits.is_some(),
+            &[Format::Json],
+        ),
+    ];
+    for &(name, given, owners) in format_options {
+        if given && !owners.contains(&format) {
+            let names: Vec<String> = owners.iter().map(Format::to_string).collect();
+            return Err(format!(
+                "opcja {} dotyczy tylko {} {} (wybrano {})",
+                name,
+                if owners.len() == 1 {
+                    "formatu"
+                } else {
+                    "formatów"
+                },
+                names.join(", "),
+                format
+            ));
+        }
+    }
+
+    let algorithm = collected.algorithm.unwrap_or_default();
+    // Opcje, które mają sens tylko dla wybranych algorytmów
+    let specific: &[(&str, bool, &[Algorithm])] = &[
+        ("--key", collected.key.is_some(), &[Algorithm::ChaCha20]),
+        ("--nonce", collected.nonce.is_some(), &[Algorithm::ChaCha20]),
+        (
+            "--extraction",
+            collected.extraction.is_some(),
+            &[Algorithm::ChaCha20, Algorithm::System],
+        ),
+        ("--seq", collected.seq.is_some(), &[Algorithm::Pcg32]),
+        (
+            "--jump",
+            collected.jump.is_some(),
+            &[Algorithm::Xoshiro256StarStar],
+        ),
+        (
+            "--long-jump",
+            collected.long_jump.is_some(),
+            &[Algorithm::Xoshiro256StarStar],
+        ),
+        ("--a", collected.lcg_a.is_some(), &[Algorithm::Lcg]),
+        ("--c", collected.lcg_c.is_some(), &[Algorithm::Lcg]),
+        ("--m", collected.lcg_m.is_some(), &[Algorithm::Lcg]),
+        (
+            "--multiplier",
+            collected.pm_multiplier.is_some(),
+            &[Algorithm::ParkMiller],
+        ),
+        ("--r", collected.awc_r.is_some(), &[Algorithm::Awc]),
+        ("--s", collected.awc_s.is_some(), &[Algorithm::Awc]),
+        ("--base", collected.awc_base.is_some(), &[Algorithm::Awc]),
+        (
+            "--variant",
+            collected.awc_variant.is_some(),
+            &[Algorithm::Awc],
+        ),
+        ("--p", collected.bbs_p.is_some(), &[Algorithm::Bbs]),
+        ("--q", collected.bbs_q.is_some(), &[Algorithm::Bbs]),
+        (
+            "--init",
+            collected.mt_init.is_some(),
+            &[Algorithm::Mt19937, Algorithm::Mt19937_64],
+        ),
+    ];
+    for &(name, given, owners) in specific {
+        if given && !owners.contains(&algorithm) {
+            let names: Vec<&str> = owners.iter().map(Algorithm::name).collect();
+            return Err(format!(
+                "opcja {} dotyczy tylko {} {}",
+                name,
+                if owners.len() == 1 {
+                    "algorytmu"
+                } else {
+                    "algorytmów"
+                },
+                names.join(", ")
+            ));
+        }
+    }
+
+    let default_bits_per_value = match algorithm {
+        Algorithm::Lcg => {
+            let m = collected.lcg_m.unwrap_or(lcg::DEFAULT_M);
+            lcg::validate_parameters(m)?;
+            lcg::default_bits_per_value(m)
+        }
+        Algorithm::ParkMiller => {
+            park_miller::validate_parameters(
+                collected.seed.unwrap_or(park_miller::DEFAULT_SEED),
+                collected
+                    .pm_multiplier
+                    .unwrap_or(park_miller::DEFAULT_MULTIPLIER),
+            )?;
+            park_miller::DEFAULT_BITS_PER_VALUE
+        }
+        Algorithm::Awc => {
+            let base = collected.awc_base.unwrap_or(awc::DEFAULT_BASE);
+            awc::validate_parameters(
+                collected.awc_r.unwrap_or(awc::DEFAULT_R),
+                collected.awc_s.unwrap_or(awc::DEFAULT_S),
+                base,
+            )?;
+            awc::default_bits_per_value(base)
+        }
+        Algorithm::Bbs => {
+            let modulus = bbs::validate_primes(
+                collected
+                    .bbs_p
+                    .as_ref()
+                    .unwrap_or(&BigUint::from(bbs::DEFAULT_P)),
+                collected
+                    .bbs_q
+                    .as_ref()
+                    .unwrap_or(&BigUint::from(bbs::DEFAULT_Q)),
+            )?;
+            bbs::validate_seed(
+                &BigUint::from(collected.seed.unwrap_or(bbs::DEFAULT_SEED)),
+                &modulus,
+            )?;
+            if let Some(bits_per_value) = collected.bits_per_value {
+                bbs::validate_bits_per_value(bits_per_value, &modulus)?;
+            }
+            bbs::DEFAULT_BITS_PER_VALUE
+        }
+        Algorithm::Mt19937 => {
+            if collected.mt_init.unwrap_or(mt19937::DEFAULT_INIT) == MtInit::InitGenrand {
+                mt19937::validate_genrand_seed(collected.seed.unwrap_or(mt19937::DEFAULT_SEED))?;
+            }
+            mt19937::DEFAULT_BITS_PER_VALUE
+        }
+        _ => algorithm.default_bits_per_value(),
+    };
+
+    let default_n_bits = match mode {
+        Mode::Generate => DEFAULT_N_BITS,
+        Mode::Test => DEFAULT_TEST_BITS,
+        Mode::LinearComplexity => DEFAULT_LFSR_BITS,
+    };
+    let n_bits = collected.n_bits.unwrap_or(default_n_bits);
+    let bits_per_value = collected.bits_per_value.unwrap_or(default_bits_per_value);
+    let extraction = collected.extraction.unwrap_or_default();
+
+    // 32-bitowy licznik bloku ChaCha20: strumień klucza kończy się po KEYSTREAM_LIMIT bajtach
+    if algorithm == Algorithm::ChaCha20 {
+        let needed = keystream_bytes(n_bits, bits_per_value, extraction)?;
+        if needed > KEYSTREAM_LIMIT as u128 {
+            return Err(format!(
+                "--bits {} wymaga {} bajtów strumienia klucza ChaCha20, a 32-bitowy licznik \
+                 bloku daje najwyżej {} (2^32 - 1 bloków po 64 bajty); podziel generację \
+                 na części z różnymi --nonce",
+                n_bits, needed, KEYSTREAM_LIMIT
+            ));
+        }
+    }
+
+    let options = Box::new(Options {
+        algorithm,
+        seed: collected.seed,
+        seq: collected.seq,
+        jump: collected.jump.unwrap_or(0),
+        long_jump: collected.long_jump.unwrap_or(0),
+        lcg_a: collected.lcg_a,
+        lcg_c: collected.lcg_c,
+        lcg_m: collected.lcg_m,
+        pm_multiplier: collected.pm_multiplier,
+        awc_r: collected.awc_r,
+        awc_s: collected.awc_s,
+        awc_base: collected.awc_base,
+        awc_variant: collected.awc_variant.unwrap_or_default(),
+        bbs_p: collected.bbs_p,
+        bbs_q: collected.bbs_q,
+        mt_init: collected.mt_init,
+        key: collected.key,
+        nonce: collected.nonce.unwrap_or([0u8; NONCE_LEN]),
+        n_bits,
+        bits_per_value,
+        extraction,
+        msb_first: collected.msb_first.unwrap_or(true),
+        format,
+        width: collected.width.unwrap_or(format.default_width()),
+        json_bits: collected.json_bits.unwrap_or(JsonBits::Array),
+        output: collected.output,
+    });
+
+    Ok(match mode {
+        Mode::Generate => Command::Generate(options),
+        Mode::Test => Command::Test {
+            options,
+            tests: collected.tests.unwrap_or_else(|| DEFAULT_TESTS.to_vec()),
+            alpha: collected.alpha.unwrap_or(nist::THRESHOLD),
+        },
+        Mode::LinearComplexity => Command::LinearComplexity(options),
+    })
+}
+
+// Argumenty podkomendy analyze (po słowie analyze): plik i opcje raportu
+fn parse_analyze_args<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut input: Option<PathBuf> = None;
+    let mut format: Option<Format> = None;
+    let mut msb_first: Option<bool> = None;
+    let mut n_bits: Option<u64> = None;
+    let mut tests: Option<Vec<NistTest>> = None;
+    let mut alpha: Option<f64> = None;
+    let mut output: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(Command::Help);
+        }
+
+        // "-" to stdin, więc jest nazwą pliku, nie opcją
+        if !arg.starts_with("--") {
+            set_once(&mut input, PathBuf::from(arg), "plik wejściowy")?;
+            continue;
+        }
+
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+
+        if name == "--lsb-first" || name == "--msb-first" {
+            if inline_value.is_some() {
+                return Err(format!("opcja {} nie przyjmuje wartości", name));
+            }
+            set_once(&mut msb_first, name == "--msb-first", "msb_first")?;
+            continue;
+        }
+
+        let mut value = || -> Result<String, String> {
+            match &inline_value {
+                Some(value) => Ok(value.clone()),
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("brak wartości dla {}", name)),
+            }
+        };
+
+        match name.as_str() {
+            "--format" => set_once(&mut format, value()?.parse()?, "format")?,
+            "--bits" => set_once(&mut n_bits, parse_count(&value()?, "--bits")?, "n_bits")?,
+            "--tests" => set_once(&mut tests, parse_tests(&value()?)?, "tests")?,
+            "--alpha" => set_once(&mut alpha, parse_alpha(&value()?)?, "alpha")?,
+            "--output" => set_once(&mut output, PathBuf::from(value()?), "output")?,
+            _ => {
+                return Err(format!(
+                    "opcja {} nie dotyczy podkomendy analyze (bity są czytane z pliku)",
+                    name
+                ))
+            }
+        }
+    }
+
+    Ok(Command::Analyze(Box::new(AnalyzeOptions {
+        input: input.ok_or("analyze wymaga pliku z bitami (albo - dla stdin)")?,
+        format,
+        msb_first: msb_first.unwrap_or(true),
+        n_bits,
+        tests: tests.unwrap_or_else(|| DEFAULT_TESTS.to_vec()),
+        alpha: alpha.unwrap_or(nist::THRESHOLD),
+        output,
+    })))
+}
+
+/*
+Uruchamia generator wybrany w `options` i przepuszcza jego bity do `sink`.
+Zwraca metadane do wyniku JSON (algorytm, seed i parametry generatora);
+sink.finish wywołuje run, bo dopiero tam znany jest czas wykonania.
+*/
+pub fn generate(options: &Options, sink: &mut dyn BitSink) -> io::Result<Map<String, Value>> {
+    let mut metadata = Map::new();
+    metadata.insert(
+        "algorithm".to_string(),
+        json!(options.algorithm.to_string()),
+    );
+    metadata.insert("seed".to_string(), json!(options.seed));
+
+    match options.algorithm {
+        Algorithm::ChaCha20 => {
+            let key = match (options.key, options.seed) {
+                (Some(key), _) => key,
+                (None, Some(seed)) => derive_key(seed),
+                (None, None) => [0u8; KEY_LEN],
+            };
+            let nonce = options.nonce;
+
+            metadata.insert(
+                "extraction".to_string(),
+                json!(options.extraction.to_string()),
+            );
+            metadata.insert("key".to_string(), json!(to_hex(&key)));
+            metadata.insert("nonce".to_string(), json!(to_hex(&nonce)));
+
+            ChaCha20Generator::new(key, nonce).stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                options.extraction,
+                sink,
+            )?;
+        }
+        Algorithm::System => {
+            let mut generator = SystemRng::new()?;
+
+            // Entropia systemu nie ma seeda — wynik jest nieodtwarzalny
+            metadata.insert("seed_ignored".to_string(), json!(true));
+            metadata.insert("source".to_string(), json!(generator.source().to_string()));
+            metadata.insert(
+                "extraction".to_string(),
+                json!(options.extraction.to_string()),
+            );
+            if let Some(seed) = options.seed {
+                metadata.insert(
+                    "warnings".to_string(),
+                    json!([format!(
+                        "seed {} jest ignorowany: algorytm system czyta entropię systemu operacyjnego",
+                        seed
+                    )]),
+                );
+            }
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                options.extraction,
+                sink,
+            )?;
+        }
+        Algorithm::Pcg32 => {
+            let initstate = options.seed.unwrap_or(pcg32::DEFAULT_INITSTATE);
+            let seq = options.seq.unwrap_or(pcg32::DEFAULT_SEQ);
+
+            metadata.insert("initstate".to_string(), json!(initstate));
+            metadata.insert("seq".to_string(), json!(seq));
+
+            Pcg32::new(initstate, seq).stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::SplitMix64 => {
+            let seed = options.seed.unwrap_or(splitmix64::DEFAULT_SEED);
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+
+            SplitMix64::new(seed).stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::Xoshiro256StarStar => {
+            let seed = options.seed.unwrap_or(xoshiro256::DEFAULT_SEED);
+            let mut generator = Xoshiro256StarStar::new(seed);
+            for _ in 0..options.long_jump {
+                generator.long_jump();
+            }
+            for _ in 0..options.jump {
+                generator.jump();
+            }
+
+            // Stan po skokach, od którego zaczyna się wynik
+            let state: Vec<String> = generator
+                .state()
+                .iter()
+                .map(|word| format!("{:016x}", word))
+                .collect();
+            metadata.insert("state".to_string(), json!(state));
+            metadata.insert("jump".to_string(), json!(options.jump));
+            metadata.insert("long_jump".to_string(), json!(options.long_jump));
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::Lcg => {
+            let seed = options.seed.unwrap_or(lcg::DEFAULT_SEED);
+            let a = options.lcg_a.unwrap_or(lcg::DEFAULT_A);
+            let c = options.lcg_c.unwrap_or(lcg::DEFAULT_C);
+            let m = options.lcg_m.unwrap_or(lcg::DEFAULT_M);
+            let mut generator = Lcg::new(seed as u128, a, c, m)
+                .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+
+            // a i c po redukcji modulo m mieszczą się w u64; m = 2^64 już nie
+            let warnings = lcg::hull_dobell_warnings(a, c, m);
+            metadata.insert("a".to_string(), json!((a % m) as u64));
+            metadata.insert("c".to_string(), json!((c % m) as u64));
+            metadata.insert("m".to_string(), json_integer(m));
+            metadata.insert("full_period".to_string(), json!(warnings.is_empty()));
+            metadata.insert("warnings".to_string(), json!(warnings));
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::ParkMiller => {
+            let seed = options.seed.unwrap_or(park_miller::DEFAULT_SEED);
+            let a = options
+                .pm_multiplier
+                .unwrap_or(park_miller::DEFAULT_MULTIPLIER);
+            let mut generator = ParkMiller::new(seed, a)
+                .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+            metadata.insert("a".to_string(), json!(a));
+            metadata.insert("m".to_string(), json!(park_miller::MODULUS));
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::Awc => {
+            let seed = options.seed.unwrap_or(awc::DEFAULT_SEED);
+            let r = options.awc_r.unwrap_or(awc::DEFAULT_R);
+            let s = options.awc_s.unwrap_or(awc::DEFAULT_S);
+            let base = options.awc_base.unwrap_or(awc::DEFAULT_BASE);
+            let mut generator = Awc::new(seed, r, s, base, options.awc_variant)
+                .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+            metadata.insert(
+                "variant".to_string(),
+                json!(options.awc_variant.to_string()),
+            );
+            metadata.insert("r".to_string(), json!(r));
+            metadata.insert("s".to_string(), json!(s));
+            metadata.insert("base".to_string(), json_integer(base));
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::Bbs => {
+            let seed = options.seed.unwrap_or(bbs::DEFAULT_SEED);
+            let p = options
+                .bbs_p
+                .clone()
+                .unwrap_or(BigUint::from(bbs::DEFAULT_P));
+            let q = options
+                .bbs_q
+                .clone()
+                .unwrap_or(BigUint::from(bbs::DEFAULT_Q));
+            let invalid = |message| io::Error::new(io::ErrorKind::InvalidInput, message);
+            let mut generator = Bbs::new(&p, &q, &BigUint::from(seed)).map_err(invalid)?;
+            bbs::validate_bits_per_value(options.bits_per_value, generator.modulus())
+                .map_err(invalid)?;
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+            metadata.insert("p".to_string(), json_integer(&p));
+            metadata.insert("q".to_string(), json_integer(&q));
+            metadata.insert("n".to_string(), json_integer(generator.modulus()));
+            metadata.insert(
+                "max_bits_per_value".to_string(),
+                json!(generator.max_bits_per_value()),
+            );
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::Mt19937 => {
+            let seed = options.seed.unwrap_or(mt19937::DEFAULT_SEED);
+            let init = options.mt_init.unwrap_or(mt19937::DEFAULT_INIT);
+            let mut generator = Mt19937::seeded(seed, init)
+                .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+            metadata.insert("init".to_string(), json!(init.to_string()));
+            if init == MtInit::InitByArray {
+                metadata.insert("key".to_string(), json!(mt19937::python_key(seed)));
+            }
+
+            generator.stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+        Algorithm::Mt19937_64 => {
+            let seed = options.seed.unwrap_or(mt19937::DEFAULT_SEED);
+            let init = options.mt_init.unwrap_or(mt19937::DEFAULT_INIT_64);
+
+            metadata.insert("initial_state".to_string(), json!(seed));
+            metadata.insert("init".to_string(), json!(init.to_string()));
+            if init == MtInit::InitByArray {
+                metadata.insert("key".to_string(), json!([seed]));
+            }
+
+            Mt19937_64::seeded(seed, init).stream_bits(
+                options.n_bits,
+                options.bits_per_value,
+                options.msb_first,
+                sink,
+            )?;
+        }
+    }
+
+    Ok(metadata)
+}
+
+// Liczba w JSON: jako liczba, jeśli mieści się w u64, inaczej jako napis
+fn json_integer(value: impl fmt::Display) -> Value {
+    let text = value.to_string();
+    match text.parse::<u64>() {
+        Ok(number) => json!(number),
+        Err(_) => json!(text),
+    }
+}
+
+// Pełne wywołanie binarki: `program` to nazwa pokazywana w pomocy
+pub fn run<I>(program: &str, args: I) -> Result<(), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    // Pełna pomoc tylko dla --help; przy błędzie wystarczy wskazówka
+    let command = parse_args(args).map_err(|message| {
+        format!(
+            "{}\nUruchom `{} --help`, aby zobaczyć opcje.",
+            message, program
+        )
+    })?;
+    let options = match command {
+        Command::Help => {
+            print!("{}", usage(program));
+            return Ok(());
+        }
+        Command::Generate(options) => *options,
+        Command::Test {
+            options,
+            tests,
+            alpha,
+        } => return run_tests(&options, &tests, alpha),
+        Command::Analyze(options) => return run_analyze(&options),
+        Command::LinearComplexity(options) => return run_linear_complexity(&options),
+    };
+
+    let writer = open_output(&options.output)?;
+    let mut sink = create_sink(
+        options.format,
+        options.json_bits,
+        options.width,
+        options.msb_first,
+        writer,
+    );
+
+    // Generuj bity prosto do wyjścia
+    let start = Instant::now();
+    let result = generate(&options, sink.as_mut()).and_then(|metadata| {
+        let elapsed = start.elapsed().as_secs_f64();
+
+        // Formaty bez metadanych nie zmieszczą ostrzeżeń — wypisz je na stderr
+        if options.format != Format::Json {
+            if let Some(Value::Array(warnings)) = metadata.get("warnings") {
+                for warning in warnings.iter().filter_map(Value::as_str) {
+                    eprintln!("Warning: {}", warning);
+                }
+            }
+        }
+        sink.finish(elapsed, metadata)
+    });
+
+    finish_output(result)
+}
+
+// Plik --output albo stdout, buforowane
+fn open_output(output: &Option<PathBuf>) -> Result<BufWriter<Box<dyn Write>>, String> {
+    let writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(
+            File::create(path)
+                .map_err(|err| format!("nie można utworzyć {}: {}", path.display(), err))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    Ok(BufWriter::with_capacity(1 << 16, writer))
+}
+
+fn finish_output(result: io::Result<()>) -> Result<(), String> {
+    match result {
+        Ok(()) => Ok(()),
+        // Odbiorca zamknął potok (np. `| head`, RNG_test) — to nie jest błąd
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(format!("nie udało się zapisać wyniku: {}", err)),
+    }
+}
+
+/*
+Bufor na cały ciąg podkomend test i linear-complexity: n/8 bajtów rezerwowanych
+z góry przez try_reserve, więc --bits ponad dostępną pamięć kończy się błędem,
+a nie przerwaniem procesu przy alokacji.
+*/
+fn packed_bits_for(n_bits: u64) -> Result<PackedBits, String> {
+    usize::try_from(n_bits)
+        .ok()
+        .and_then(|n| PackedBits::try_with_capacity(n).ok())
+        .ok_or_else(|| {
+            format!(
+                "--bits {} nie mieści się w pamięci (ciąg zajmuje {} bajtów)",
+                n_bits,
+                n_bits.div_ceil(8)
+            )
+        })
+}
+
+/*
+Podkomenda test: bity trafiają do pamięci procesu spakowane w PackedBits (bit
+na bit, bez JSON-a i bez listy Pythona), a na wyjście idzie tylko raport
+nist::report uzupełniony o metadane generatora i czas generacji
+(generation_time).
+*/
+fn run_tests(options: &Options, tests: &[NistTest], alpha: f64) -> Result<(), String> {
+    let mut bits = packed_bits_for(options.n_bits)?;
+    let writer = open_output(&options.output)?;
+
+    let start = Instant::now();
+    let metadata = generate(options, &mut bits)
+        .map_err(|err| format!("generator zakończył się błędem: {}", err))?;
+    let generation_time = start.elapsed().as_secs_f64();
+
+    let mut report = nist::report(&bits, tests, alpha);
+    report.insert("generation_time".to_string(), json!(generation_time));
+    report.extend(metadata);
+
+    write_report(writer, &report)
+}
+
+/*
+Podkomenda analyze: bity z pliku (albo stdin) w formacie rozpoznanym przez
+input::detect_format lub podanym w --format, zdekodowane wprost do PackedBits,
+i ten sam raport co dla test, z polami input, format (i bit_order) zamiast
+metadanych generatora.
+*/
+fn run_analyze(options: &AnalyzeOptions) -> Result<(), String> {
+    let source = options.input.display().to_string();
+    let data = if source == "-" {
+        let mut data = Vec::new();
+        io::stdin()
+            .read_to_end(&mut data)
+            .map_err(|err| format!("nie można odczytać stdin: {}", err))?;
+        data
+    } else {
+        std::fs::read(&options.input)
+            .map_err(|err| format!("nie można odczytać {}: {}", source, err))?
+    };
+    let writer = open_output(&options.output)?;
+
+    let format = options
+        .format
+        .unwrap_or_else(|| input::detect_format(&data));
+    let mut bits = input::read_bits(&data, format, options.msb_first)
+        .map_err(|message| format!("{} ({}): {}", source, format, message))?;
+    // Testy widzą już tylko PackedBits (n/8 bajtów), plik nie jest potrzebny
+    drop(data);
+    if bits.is_empty() {
+        return Err(format!("{} nie zawiera bitów", source));
+    }
+    if let Some(n_bits) = options.n_bits {
+        if n_bits > bits.len() as u64 {
+            return Err(format!(
+                "--bits {} przekracza liczbę bitów w {} ({})",
+                n_bits,
+                source,
+                bits.len()
+            ));
+        }
+        bits.truncate(n_bits as usize);
+    }
+
+    let mut report = nist::report(&bits, &options.tests, options.alpha);
+    report.insert("input".to_string(), json!(source));
+    report.insert("format".to_string(), json!(format.to_string()));
+    // Kolejność bitów w bajcie ma znaczenie tylko dla formatów bajtowych
+    if matches!(format, Format::Raw | Format::Hex | Format::Base64) {
+        report.insert(
+            "bit_order".to_string(),
+            json!(if options.msb_first { "msb" } else { "lsb" }),
+        );
+    }
+
+    write_report(writer, &report)
+}
+
+/*
+Podkomenda linear-complexity: bity w PackedBits jak dla test, raport lfsr::report
+z profilem całego ciągu i każdej z bits_per_value pozycji bitu w wartości.
+*/
+fn run_linear_complexity(options: &Options) -> Result<(), String> {
+    let mut bits = packed_bits_for(options.n_bits)?;
+    let writer = open_output(&options.output)?;
+
+    let start = Instant::now();
+    let metadata = generate(options, &mut bits)
+        .map_err(|err| format!("generator zakończył się błędem: {}", err))?;
+    let generation_time = start.elapsed().as_secs_f64();
+
+    let mut report = lfsr::report(&bits, options.bits_per_value);
+    report.insert("generation_time".to_string(), json!(generation_time));
+    report.extend(metadata);
+
+    write_report(writer, &report)
+}
+
+// Raport testów jako jedna linia JSON
+fn write_report(
+    mut writer: BufWriter<Box<dyn Write>>,
+    report: &Map<String, Value>,
+) -> Result<(), String> {
+    finish_output(
+        serde_json::to_writer(&mut writer, report)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(writer))
+            .and_then(|()| writer.flush()),
+    )
+}
@@ -0,0 +1,755 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/*
+Szybka transformata Fouriera dla testu widmowego NIST (nist::dft).
+
+    Fft::new(len).process(buffer)     -- DFT w miejscu, dowolna długość,
+                                          X_k = sum x_j e^{-2 pi i j k / len}
+    real_spectrum_magnitudes(n, budget, sample, visit)
+                                       -- |X_k| dla k < n/2 ciągu rzeczywistego
+                                          x_j = sample(j), wywołuje visit(k, |X_k|),
+                                          bufory FFT w budget bajtów
+    SpectrumPlan::new(n, budget)       -- plan real_spectrum_magnitudes,
+                                          .memory(n) to jego szczyt w bajtach
+
+Fft to Cooley–Tukey z podziałem w czasie (DIT), liczony rekurencyjnie w miejscu
+po permutacji odwracającej cyfry. Długość jest rozkładana na czynniki ułożone
+palindromowo (np. 500000 = [4,5,5,5,2,5,5,5,4]): pary tych samych czynników
+idą na oba końce, a czynniki o nieparzystej krotności łączą się w jeden środkowy.
+Wtedy permutacja jest inwolucją i wystarczą zamiany par. Małe czynniki (2, 4,
+do NAIVE_RADIX_LIMIT) mają motylki liczone wprost, większe — algorytm
+Bluesteina (splot z chirpem przez FFT potęgi dwójki), więc każda długość jest
+O(n log n), także liczba pierwsza.
+
+Pamięć: poza samym buforem tablice twiddle o rozmiarze O(sqrt(n))
+(w^k = coarse[k >> s] * fine[k & mask], oba czynniki z sin_cos, błąd ~2 ulp),
+a dla czynnika r > NAIVE_RADIX_LIMIT bufory Bluesteina: r elementów i dwa
+wektory długości potęgi dwójki >= 2r - 1 (do 144 bajtów na element czynnika).
+Długości gładkie (potęgi dwójki, 10^k) ich nie potrzebują; duży czynnik pierwszy
+(skrajnie: cała długość pierwsza) kosztuje kilka razy więcej niż sam bufor.
+Pamięć Fft zależy więc od rozkładu długości, dlatego real_spectrum_magnitudes
+wybiera plan (SpectrumPlan), którego bufory mieszczą się w budżecie:
+
+    Whole          -- cała transformata naraz: parzysty ciąg rzeczywisty
+                      pakowany w n/2 liczb zespolonych (z_j = x_2j + i x_2j+1,
+                      8 B na próbkę), nieparzysty jako pełny bufor (16 B)
+    Cosets(c)      -- najmniejszy dzielnik c <= MAX_COSETS, przy którym warstwa
+                      X_(s + c t) (FFT długości n / c) mieści się w budżecie;
+                      wystarczy c/2 + 1 warstw, bo |X_(n-k)| = |X_k|
+    Chirp          -- n bez takiego dzielnika (duży czynnik pierwszy): Bluestein
+                      całej długości liczony warstwami potęgi dwójki
+                      w połowie budżetu; druga połowa to akumulator wyników,
+                      a gdy n/2 wartości się w nim nie mieści, warstwy są
+                      przeliczane dla kolejnych przedziałów k
+
+Moduły idą do visit w trakcie liczenia, widmo nigdy nie jest przechowywane.
+Przy budżecie 1 GiB (nist::DFT_MEMORY) n = 2^30 to Cosets(32): ok. 17 przejść
+po ciągu. Chirp kosztuje O(n^3 / budżet^2): n pierwsze rzędu 2^27 to około
+dwóch minut, rzędu 2^30 — godziny, ale w tej samej pamięci.
+*/
+
+// Czynniki do tej wielkości mają motylek O(r^2), większe idą przez Bluesteina
+const NAIVE_RADIX_LIMIT: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    pub fn scale(self, factor: f64) -> Self {
+        Complex::new(self.re * factor, self.im * factor)
+    }
+
+    // Mnożenie przez -i
+    fn rotate(self) -> Self {
+        Complex::new(self.im, -self.re)
+    }
+
+    pub fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+// Pierwiastki z jedynki w^k = e^{-2 pi i k / order} z dwóch tablic po ~sqrt(order)
+struct Twiddles {
+    shift: u32,
+    fine: Vec<Complex>,
+    coarse: Vec<Complex>,
+}
+
+impl Twiddles {
+    fn new(order: usize) -> Self {
+        let bits = usize::BITS - order.max(1).leading_zeros();
+        let shift = bits.div_ceil(2);
+        let root = |k: usize| {
+            let (sin, cos) = (-2.0 * PI * k as f64 / order as f64).sin_cos();
+            Complex::new(cos, sin)
+        };
+        Twiddles {
+            shift,
+            fine: (0..1usize << shift).map(root).collect(),
+            coarse: (0..=order >> shift).map(|k| root(k << shift)).collect(),
+        }
+    }
+
+    // k < order
+    fn get(&self, k: usize) -> Complex {
+        let fine = self.fine[k & ((1 << self.shift) - 1)];
+        match k >> self.shift {
+            0 => fine,
+            high => self.coarse[high] * fine,
+        }
+    }
+}
+
+// Bluestein: DFT długości r jako splot z chirpem c_q = e^{-pi i q^2 / r}
+struct Bluestein {
+    chirp: Vec<Complex>,
+    // FFT filtra conj(c) o długości potęgi dwójki >= 2r - 1, już podzielone przez tę długość
+    filter: Vec<Complex>,
+    fft: Fft,
+    scratch: Vec<Complex>,
+}
+
+impl Bluestein {
+    fn new(r: usize) -> Self {
+        let m = (2 * r - 1).next_power_of_two();
+        let chirp: Vec<Complex> = (0..r as u64)
+            .map(|q| {
+                // q^2 mod 2r liczone dokładnie, kąt zawsze w [0, 2 pi)
+                let t = (q * q) % (2 * r as u64);
+                let (sin, cos) = (-PI * t as f64 / r as f64).sin_cos();
+                Complex::new(cos, sin)
+            })
+            .collect();
+
+        let mut filter = vec![Complex::ZERO; m];
+        for (q, c) in chirp.iter().enumerate() {
+            filter[q] = c.conj();
+            if q > 0 {
+                filter[m - q] = c.conj();
+            }
+        }
+        let mut fft = Fft::new(m);
+        fft.process(&mut filter);
+        for value in &mut filter {
+            *value = value.scale(1.0 / m as f64);
+        }
+
+        Bluestein {
+            chirp,
+            filter,
+            fft,
+            scratch: vec![Complex::ZERO; m],
+        }
+    }
+
+    fn process(&mut self, values: &mut [Complex]) {
+        let r = values.len();
+        for (slot, (&x, &c)) in self.scratch.iter_mut().zip(values.iter().zip(&self.chirp)) {
+            *slot = x * c;
+        }
+        self.scratch[r..].fill(Complex::ZERO);
+        self.fft.process(&mut self.scratch);
+        // Odwrotna FFT jako conj(FFT(conj(.)))
+        for (slot, &f) in self.scratch.iter_mut().zip(&self.filter) {
+            *slot = (*slot * f).conj();
+        }
+        self.fft.process(&mut self.scratch);
+        for (x, (&s, &c)) in values.iter_mut().zip(self.scratch.iter().zip(&self.chirp)) {
+            *x = s.conj() * c;
+        }
+    }
+}
+
+enum Butterfly {
+    Two,
+    Four,
+    // Pierwiastki w_r^t dla t < r
+    Naive(Vec<Complex>),
+    Bluestein(Box<Bluestein>),
+}
+
+pub struct Fft {
+    len: usize,
+    radices: Vec<usize>,
+    butterflies: Vec<Butterfly>,
+    twiddles: Twiddles,
+    // Bufor na r elementów motylka liczonego wprost albo przez Bluesteina
+    gathered: Vec<Complex>,
+}
+
+// Liczba elementów tablic Twiddles::new(order)
+fn twiddles_len(order: usize) -> usize {
+    let bits = usize::BITS - order.max(1).leading_zeros();
+    let shift = bits.div_ceil(2);
+    (1 << shift) + (order >> shift) + 1
+}
+
+// Liczba elementów Complex zaalokowanych przez Fft::new(len) (bez samego bufora)
+fn fft_memory(len: usize) -> usize {
+    let radices = palindromic_radices(len);
+    let butterflies: usize = radices
+        .iter()
+        .map(|&r| match r {
+            2 | 4 => 0,
+            r if r <= NAIVE_RADIX_LIMIT => r,
+            r => {
+                // chirp, filter i scratch oraz FFT potęgi dwójki
+                let m = (2 * r - 1).next_power_of_two();
+                r + 2 * m + fft_memory(m)
+            }
+        })
+        .sum();
+    twiddles_len(len) + butterflies + radices.iter().copied().max().unwrap_or(1)
+}
+
+// Rozkład len na czynniki ułożone palindromowo (patrz komentarz na górze)
+fn palindromic_radices(len: usize) -> Vec<usize> {
+    let mut factors: Vec<(usize, u32)> = Vec::new();
+    let mut rest = len;
+    let mut p = 2;
+    while p * p <= rest {
+        let mut exponent = 0;
+        while rest.is_multiple_of(p) {
+            rest /= p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        p += 1;
+    }
+    if rest > 1 {
+        factors.push((rest, 1));
+    }
+
+    let mut outer = Vec::new();
+    let mut middle = 1;
+    for (p, exponent) in factors {
+        let mut pairs = exponent / 2;
+        // Pary dwójek po tej samej stronie łączą się w czwórki
+        if p == 2 {
+            outer.extend(std::iter::repeat_n(4, pairs as usize / 2));
+            pairs %= 2;
+        }
+        outer.extend(std::iter::repeat_n(p, pairs as usize));
+        if exponent % 2 == 1 {
+            middle *= p;
+        }
+    }
+
+    let mut radices = outer.clone();
+    if middle > 1 {
+        radices.push(middle);
+    }
+    radices.extend(outer.iter().rev());
+    radices
+}
+
+impl Fft {
+    pub fn new(len: usize) -> Self {
+        let radices = palindromic_radices(len);
+        let twiddles = Twiddles::new(len);
+        let butterflies = radices
+            .iter()
+            .map(|&r| match r {
+                2 => Butterfly::Two,
+                4 => Butterfly::Four,
+                r if r <= NAIVE_RADIX_LIMIT => {
+                    Butterfly::Naive((0..r).map(|t| twiddles.get(t * (len / r))).collect())
+                }
+                r => Butterfly::Bluestein(Box::new(Bluestein::new(r))),
+            })
+            .collect();
+        let largest = radices.iter().copied().max().unwrap_or(1);
+        Fft {
+            len,
+            radices,
+            butterflies,
+            twiddles,
+            gathered: vec![Complex::ZERO; largest],
+        }
+    }
+
+    // Transformata w przód w miejscu; buffer.len() musi być równe len
+    pub fn process(&mut self, buffer: &mut [Complex]) {
+        assert_eq!(
+            buffer.len(),
+            self.len,
+            "długość bufora różna od długości FFT"
+        );
+        self.reverse_digits(buffer);
+        if !self.radices.is_empty() {
+            self.transform(buffer, self.radices.len() - 1);
+        }
+    }
+
+    // Element z pozycji o cyfrach (d_0, ..., d_m-1) w systemie radices trafia
+    // na pozycję o cyfrach odwróconych; przy palindromie to inwolucja
+    fn reverse_digits(&self, buffer: &mut [Complex]) {
+        let m = self.radices.len();
+        let mut weights = vec![1usize; m];
+        for s in (0..m.saturating_sub(1)).rev() {
+            weights[s] = weights[s + 1] * self.radices[s + 1];
+        }
+
+        let mut digits = vec![0usize; m];
+        let mut reversed = 0usize;
+        for index in 0..self.len {
+            if index < reversed {
+                buffer.swap(index, reversed);
+            }
+            for s in 0..m {
+                digits[s] += 1;
+                reversed += weights[s];
+                if digits[s] < self.radices[s] {
+                    break;
+                }
+                reversed -= self.radices[s] * weights[s];
+                digits[s] = 0;
+            }
+        }
+    }
+
+    // Łączy radices[level] kolejnych pod-transformat długości buffer.len() / r
+    fn transform(&mut self, buffer: &mut [Complex], level: usize) {
+        let len = buffer.len();
+        let r = self.radices[level];
+        let m = len / r;
+        if level > 0 {
+            for chunk in buffer.chunks_exact_mut(m) {
+                self.transform(chunk, level - 1);
+            }
+        }
+
+        // w_len^(j q) = w_N^(j q N / len)
+        let step = self.len / len;
+        let twiddles = &self.twiddles;
+        let twiddle = |k: usize| twiddles.get(k * step);
+        match &mut self.butterflies[level] {
+            Butterfly::Two => {
+                let (low, high) = buffer.split_at_mut(m);
+                for (j, (a, b)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                    let t = *b * twiddle(j);
+                    (*a, *b) = (*a + t, *a - t);
+                }
+            }
+            Butterfly::Four => {
+                for j in 0..m {
+                    let a0 = buffer[j];
+                    let a1 = buffer[j + m] * twiddle(j);
+                    let a2 = buffer[j + 2 * m] * twiddle(2 * j);
+                    let a3 = buffer[j + 3 * m] * twiddle(3 * j);
+                    let (t0, t1) = (a0 + a2, a0 - a2);
+                    let (t2, t3) = (a1 + a3, (a1 - a3).rotate());
+                    buffer[j] = t0 + t2;
+                    buffer[j + m] = t1 + t3;
+                    buffer[j + 2 * m] = t0 - t2;
+                    buffer[j + 3 * m] = t1 - t3;
+                }
+            }
+            Butterfly::Naive(roots) => {
+                let gathered = &mut self.gathered[..r];
+                for j in 0..m {
+                    for (q, slot) in gathered.iter_mut().enumerate() {
+                        *slot = buffer[j + q * m] * twiddle(j * q);
+                    }
+                    for p in 0..r {
+                        let mut sum = gathered[0];
+                        for (q, &a) in gathered.iter().enumerate().skip(1) {
+                            sum = sum + a * roots[p * q % r];
+                        }
+                        buffer[j + p * m] = sum;
+                    }
+                }
+            }
+            Butterfly::Bluestein(bluestein) => {
+                let gathered = &mut self.gathered[..r];
+                for j in 0..m {
+                    for (q, slot) in gathered.iter_mut().enumerate() {
+                        *slot = buffer[j + q * m] * twiddle(j * q);
+                    }
+                    bluestein.process(gathered);
+                    for (p, &y) in gathered.iter().enumerate() {
+                        buffer[j + p * m] = y;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Najwięcej warstw widma w SpectrumPlan::Cosets (każda to osobne przejście po ciągu)
+const MAX_COSETS: usize = 256;
+
+/*
+Sposób liczenia widma real_spectrum_magnitudes(n, budget) — patrz komentarz na
+górze. Cosets(c) i Chirp trzymają naraz tylko jedną warstwę widma: c i cosets
+to liczba warstw, len długość FFT warstwy, a ranges liczba przedziałów k,
+na które Chirp dzieli akumulator (każdy przedział liczy wszystkie warstwy).
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectrumPlan {
+    Whole,
+    Cosets(usize),
+    Chirp {
+        cosets: usize,
+        len: usize,
+        ranges: usize,
+    },
+}
+
+impl SpectrumPlan {
+    // Najtańszy plan mieszczący się w budget bajtów; budżet mniejszy niż jedna warstwa
+    // długości 1 (praktycznie same tablice twiddle) jest ignorowany — wtedy Whole
+    pub fn new(n: usize, budget: u64) -> Self {
+        let fits = |elements: usize, budget: u64| bytes(elements) <= budget;
+        if n < 2 || fits(whole_memory(n), budget) {
+            return SpectrumPlan::Whole;
+        }
+        let divisor = (2..=MAX_COSETS.min(n))
+            .find(|&c| n.is_multiple_of(c) && fits(cosets_memory(n, c), budget));
+        if let Some(c) = divisor {
+            return SpectrumPlan::Cosets(c);
+        }
+
+        // Połowa budżetu na warstwy, reszta na akumulator
+        let total = chirp_len(n);
+        let mut len = total.next_power_of_two();
+        while len > 1 && !fits(chirp_memory(n, total.div_ceil(len), len), budget / 2) {
+            len /= 2;
+        }
+        let cosets = total.div_ceil(len);
+        if !fits(chirp_memory(n, cosets, len), budget / 2) {
+            return SpectrumPlan::Whole;
+        }
+        let spare = budget.saturating_sub(bytes(chirp_memory(n, cosets, len)));
+        let range = (spare / bytes(1)).clamp(1, (n / 2) as u64) as usize;
+        SpectrumPlan::Chirp {
+            cosets,
+            len,
+            ranges: (n / 2).div_ceil(range),
+        }
+    }
+
+    // Szczyt pamięci planu w bajtach
+    pub fn memory(self, n: usize) -> u64 {
+        bytes(match self {
+            SpectrumPlan::Whole => whole_memory(n),
+            SpectrumPlan::Cosets(c) => cosets_memory(n, c),
+            SpectrumPlan::Chirp {
+                cosets,
+                len,
+                ranges,
+            } => chirp_memory(n, cosets, len) + (n / 2).div_ceil(ranges),
+        })
+    }
+}
+
+fn bytes(elements: usize) -> u64 {
+    elements as u64 * std::mem::size_of::<Complex>() as u64
+}
+
+// Bufor, tablice twiddle i Bluestein całej transformaty (parzyste n pakowane parami)
+fn whole_memory(n: usize) -> usize {
+    match n {
+        0 | 1 => 0,
+        n if n % 2 == 1 => n + fft_memory(n),
+        n => n / 2 + fft_memory(n / 2) + twiddles_len(n),
+    }
+}
+
+// Jedna warstwa długości n / c z jej FFT, twiddle rzędu n i pierwiastki rzędu c
+fn cosets_memory(n: usize, c: usize) -> usize {
+    let m = n / c;
+    m + fft_memory(m) + twiddles_len(n) + c
+}
+
+// Długość splotu cyklicznego w Chirp: n próbek i n/2 wyjść bez zawinięcia
+fn chirp_len(n: usize) -> usize {
+    n + n / 2 - 1
+}
+
+// Warstwy sygnału i filtru długości len z FFT, twiddle splotu i chirpu, pierwiastki
+fn chirp_memory(n: usize, cosets: usize, len: usize) -> usize {
+    2 * len + fft_memory(len) + twiddles_len(cosets * len) + twiddles_len(2 * n) + 2 * cosets
+}
+
+/*
+Moduły |X_k| dla k < n/2 transformaty ciągu rzeczywistego x_j = sample(j),
+j < n, przekazywane do visit(k, |X_k|) — każde k dokładnie raz, ale rosnąco
+tylko w planach Whole i Chirp. Pamięć mieści się w budget bajtów
+(SpectrumPlan::new), o ile budżet wystarcza choćby na tablice twiddle.
+
+Whole, parzyste n: FFT długości h = n/2 z z_j = x_2j + i x_2j+1 rozdzielona
+
+    E_k = (Z_k + conj Z_(h-k)) / 2        (widmo próbek parzystych)
+    O_k = (Z_k - conj Z_(h-k)) / 2i       (widmo próbek nieparzystych)
+    X_k = E_k + e^{-2 pi i k / n} O_k
+
+parami (k, h - k) w tym samym buforze; nieparzyste n — pełna FFT zespolona.
+*/
+pub fn real_spectrum_magnitudes(
+    n: usize,
+    budget: u64,
+    sample: impl Fn(usize) -> f64,
+    visit: impl FnMut(usize, f64),
+) {
+    match SpectrumPlan::new(n, budget) {
+        SpectrumPlan::Whole => whole_magnitudes(n, sample, visit),
+        SpectrumPlan::Cosets(c) => coset_magnitudes(n, c, sample, visit),
+        SpectrumPlan::Chirp {
+            cosets,
+            len,
+            ranges,
+        } => chirp_magnitudes(n, cosets, len, ranges, sample, visit),
+    }
+}
+
+fn whole_magnitudes(n: usize, sample: impl Fn(usize) -> f64, mut visit: impl FnMut(usize, f64)) {
+    if n < 2 {
+        return;
+    }
+    if n % 2 == 1 {
+        let mut buffer: Vec<Complex> = (0..n).map(|j| Complex::new(sample(j), 0.0)).collect();
+        Fft::new(n).process(&mut buffer);
+        for (k, x) in buffer[..n / 2].iter().enumerate() {
+            visit(k, x.norm());
+        }
+        return;
+    }
+
+    let h = n / 2;
+    let mut buffer: Vec<Complex> = (0..h)
+        .map(|j| Complex::new(sample(2 * j), sample(2 * j + 1)))
+        .collect();
+    Fft::new(h).process(&mut buffer);
+
+    let twiddles = Twiddles::new(n);
+    let unpack = |zk: Complex, zl: Complex, k: usize| {
+        let even = (zk + zl.conj()).scale(0.5);
+        let odd = (zk - zl.conj()).scale(0.5).rotate();
+        even + twiddles.get(k) * odd
+    };
+    for k in 0..=h / 2 {
+        let l = (h - k) % h;
+        let (zk, zl) = (buffer[k], buffer[l]);
+        buffer[k].re = unpack(zk, zl, k).norm();
+        if l != k {
+            buffer[l].re = unpack(zl, zk, l).norm();
+        }
+    }
+    for (k, x) in buffer.iter().enumerate() {
+        visit(k, x.re);
+    }
+}
+
+/*
+Cosets(c), m = n / c: warstwa s to X_(s + c t), t < m, czyli FFT długości m
+ciągu złożonego z c kawałków x:
+
+    y_j = e^{-2 pi i j s / n} sum_(v < c) x_(j + m v) e^{-2 pi i v s / c}
+
+Dla rzeczywistego x |X_(n-k)| = |X_k|, a n - k leży w warstwie c - s, więc
+wystarczą warstwy s <= c/2.
+*/
+fn coset_magnitudes(
+    n: usize,
+    c: usize,
+    sample: impl Fn(usize) -> f64,
+    mut visit: impl FnMut(usize, f64),
+) {
+    let m = n / c;
+    let half = n / 2;
+    let twiddles = Twiddles::new(n);
+    let roots: Vec<Complex> = (0..c).map(|v| twiddles.get(v * m)).collect();
+    let mut fft = Fft::new(m);
+    let mut buffer = vec![Complex::ZERO; m];
+    for s in 0..=c / 2 {
+        buffer.fill(Complex::ZERO);
+        for v in 0..c {
+            let root = roots[v * s % c];
+            for (j, slot) in buffer.iter_mut().enumerate() {
+                *slot = *slot + root.scale(sample(j + m * v));
+            }
+        }
+        for (j, slot) in buffer.iter_mut().enumerate() {
+            *slot = *slot * twiddles.get(j * s);
+        }
+        fft.process(&mut buffer);
+
+        // Warstwy 0 i c/2 (przy parzystym c) są swoim własnym lustrem
+        let mirrored = s != 0 && 2 * s != c;
+        for (t, x) in buffer.iter().enumerate() {
+            let k = s + c * t;
+            if k < half {
+                visit(k, x.norm());
+            } else if mirrored && n - k < half {
+                visit(n - k, x.norm());
+            }
+        }
+    }
+}
+
+// Kolejne w_q = e^{-pi i q^2 / n} dla q = 0, 1, ...; q^2 mod 2n liczone dokładnie dodawaniem
+struct Chirps {
+    table: Twiddles,
+    order: usize,
+    square: usize,
+    step: usize,
+}
+
+impl Chirps {
+    fn new(n: usize) -> Self {
+        Chirps {
+            table: Twiddles::new(2 * n),
+            order: 2 * n,
+            square: 0,
+            step: 1,
+        }
+    }
+
+    fn next(&mut self) -> Complex {
+        let w = self.table.get(self.square);
+        // (q + 1)^2 = q^2 + (2q + 1)
+        self.square += self.step;
+        if self.square >= self.order {
+            self.square -= self.order;
+        }
+        self.step += 2;
+        if self.step >= self.order {
+            self.step -= self.order;
+        }
+        w
+    }
+}
+
+/*
+Chirp { cosets: c, len: m, ranges } dla n, którego żaden mały dzielnik nie
+daje warstwy w budżecie (duży czynnik pierwszy): Bluestein na całej długości,
+
+    X_k = w_k sum_(j < n) (x_j w_j) conj(w_(k-j)),    w_q = e^{-pi i q^2 / n},
+
+jako splot cykliczny długości M = c m >= n + n/2 - 1 (m potęga dwójki).
+Transformaty długości M są liczone warstwami jak w Cosets, iloczyn warstwy s
+wraca odwrotną FFT długości m, a jej wkład
+
+    (a * g)_k += e^{2 pi i s k / M} q_s[k mod m] / M
+
+trafia do akumulatora. Akumulator obejmuje naraz n/2 / ranges kolejnych k,
+a każdy przedział przelicza wszystkie warstwy od nowa: koszt to
+O(ranges c (n + m log m)) przy pamięci O(m + n / ranges).
+*/
+fn chirp_magnitudes(
+    n: usize,
+    c: usize,
+    m: usize,
+    ranges: usize,
+    sample: impl Fn(usize) -> f64,
+    mut visit: impl FnMut(usize, f64),
+) {
+    let size = c * m;
+    let half = n / 2;
+    let (shift, mask) = (m.trailing_zeros(), m - 1);
+    let twiddles = Twiddles::new(size);
+    let roots: Vec<Complex> = (0..c).map(|v| twiddles.get(v * m)).collect();
+    let mut coset_roots = vec![Complex::ZERO; c];
+    let mut fft = Fft::new(m);
+    let mut data = vec![Complex::ZERO; m];
+    let mut filter = vec![Complex::ZERO; m];
+    let range = half.div_ceil(ranges);
+    let mut sums = vec![Complex::ZERO; range];
+
+    for start in (0..half).step_by(range) {
+        let sums = &mut sums[..range.min(half - start)];
+        sums.fill(Complex::ZERO);
+        for s in 0..c {
+            for (v, root) in coset_roots.iter_mut().enumerate() {
+                *root = roots[v * s % c];
+            }
+            data.fill(Complex::ZERO);
+            filter.fill(Complex::ZERO);
+            // a_j = x_j w_j na pozycji j, filtr conj(w_q) na q < n/2 i na M - q dla 0 < q < n
+            let mut chirps = Chirps::new(n);
+            for j in 0..n {
+                let w = chirps.next();
+                data[j & mask] = data[j & mask] + w.scale(sample(j)) * coset_roots[j >> shift];
+                if j < half {
+                    filter[j & mask] = filter[j & mask] + w.conj() * coset_roots[j >> shift];
+                }
+                if j > 0 {
+                    let i = size - j;
+                    filter[i & mask] = filter[i & mask] + w.conj() * coset_roots[i >> shift];
+                }
+            }
+            for (u, (a, g)) in data.iter_mut().zip(filter.iter_mut()).enumerate() {
+                let twiddle = twiddles.get(u * s);
+                *a = *a * twiddle;
+                *g = *g * twiddle;
+            }
+            fft.process(&mut data);
+            fft.process(&mut filter);
+            // Odwrotna FFT jako conj(FFT(conj(.)))
+            for (a, &g) in data.iter_mut().zip(&filter) {
+                *a = (*a * g).conj();
+            }
+            fft.process(&mut data);
+
+            // e^{-2 pi i s k / M} z wykładnikiem s k mod M liczonym dodawaniem
+            let mut exponent = start * s % size;
+            for (k, sum) in (start..).zip(sums.iter_mut()) {
+                *sum = *sum + (data[k & mask] * twiddles.get(exponent)).conj();
+                exponent += s;
+                if exponent >= size {
+                    exponent -= size;
+                }
+            }
+        }
+
+        let mut chirps = Chirps::new(n);
+        for _ in 0..start {
+            chirps.next();
+        }
+        for (k, &sum) in (start..).zip(sums.iter()) {
+            visit(k, (chirps.next() * sum).norm() / size as f64);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use serde_json::Value;
+
+use crate::bits::{BitSequence, PackedBits};
+use crate::output::Format;
+
+/*
+Odczyt ciągów bitów z plików — odwrotność output.rs, dla podkomendy analyze
+(bity z TRNG, z generatorów w innych językach albo eksport generated_bits
+z Django).
+
+    read_bits(data, format, msbFirst) -> PackedBits (bit pamięci na bit ciągu)
+    detect_format(data)               -> format rozpoznany po zawartości
+
+Obsługiwane formaty (Format z output.rs):
+    json     -- {"bits": [1,0,...]} (jak --format json), {"bits_base64": "...",
+                "n_bits": N, "bit_order": "msb"} (jak --json-bits base64),
+                {"generated_bits": [...]} (wynik testu z API) albo sama tablica
+    raw      -- surowe bajty, 8 bitów na bajt
+    hex      -- cyfry szesnastkowe, białe znaki są pomijane
+    base64   -- base64 (RFC 4648), białe znaki są pomijane
+    ascii01  -- znaki '0'/'1' (format NIST STS assess), białe znaki są pomijane
+
+Kolejność bitów w bajcie (raw, hex, base64) wybiera msbFirst, jak w pack_bits;
+dla bits_base64 decyduje pole bit_order, jeśli jest. Formaty bajtowe dają
+zawsze wielokrotność 8 bitów — dopełnienie ostatniego bajtu zostaje w wyniku.
+Rozpoznawanie: JSON, gdy plik zaczyna się od '{' albo '[' i cały daje się
+odczytać jako jedna z powyższych postaci (plik binarny też może zaczynać się
+od tych bajtów), potem ascii01, hex, a wszystko inne to raw; base64 trzeba
+wybrać jawnie (jego alfabet obejmuje hex i ascii01). Jawny --format wygrywa
+z rozpoznawaniem.
+*/
+
+// Odwrotność pack_bits: każdy bajt daje 8 bitów w kolejności msb_first
+pub fn unpack_bits(bytes: &[u8], msb_first: bool) -> Vec<u8> {
+    let mut bits = Vec::with_capacity(bytes.len() * 8);
+    for &byte in bytes {
+        for i in 0..8 {
+            let shift = if msb_first { 7 - i } else { i };
+            bits.push((byte >> shift) & 1);
+        }
+    }
+    bits
+}
+
+// Znaki tekstu bez białych znaków
+fn significant(text: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    text.iter().copied().filter(|c| !c.is_ascii_whitespace())
+}
+
+pub fn from_hex(text: &[u8]) -> Result<Vec<u8>, String> {
+    let digits = significant(text)
+        .map(|c| {
+            (c as char)
+                .to_digit(16)
+                .ok_or_else(|| format!("niepoprawny znak hex: '{}'", c.escape_ascii()))
+        })
+        .collect::<Result<Vec<u32>, String>>()?;
+    if !digits.len().is_multiple_of(2) {
+        return Err(format!(
+            "nieparzysta liczba cyfr hex ({}) — brak połowy bajtu",
+            digits.len()
+        ));
+    }
+    Ok(digits
+        .chunks_exact(2)
+        .map(|pair| (pair[0] << 4 | pair[1]) as u8)
+        .collect())
+}
+
+pub fn from_base64(text: &[u8]) -> Result<Vec<u8>, String> {
+    let chars: Vec<u8> = significant(text).collect();
+    if !chars.len().is_multiple_of(4) {
+        return Err(format!(
+            "długość base64 ({}) nie jest wielokrotnością 4",
+            chars.len()
+        ));
+    }
+
+    let mut bytes = Vec::with_capacity(chars.len() / 4 * 3);
+    for (index, group) in chars.chunks_exact(4).enumerate() {
+        let last = index == chars.len() / 4 - 1;
+        let padding = group.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err("niepoprawne dopełnienie '=' w base64".to_string());
+        }
+
+        let mut triple = 0u32;
+        for &c in &group[..4 - padding] {
+            let value = match c {
+                b'A'..=b'Z' => c - b'A',
+                b'a'..=b'z' => c - b'a' + 26,
+                b'0'..=b'9' => c - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return Err(format!("niepoprawny znak base64: '{}'", c.escape_ascii())),
+            };
+            triple = (triple << 6) | value as u32;
+        }
+        triple <<= 6 * padding;
+        bytes.extend_from_slice(&triple.to_be_bytes()[1..4 - padding]);
+    }
+    Ok(bytes)
+}
+
+pub fn from_ascii01(text: &[u8]) -> Result<PackedBits, String> {
+    let mut bits = PackedBits::with_capacity(text.len());
+    for c in significant(text) {
+        match c {
+            b'0' => bits.push(0),
+            b'1' => bits.push(1),
+            _ => {
+                return Err(format!(
+                    "niepoprawny znak w ascii01: '{}' (oczekiwano '0' albo '1')",
+                    c.escape_ascii()
+                ))
+            }
+        }
+    }
+    Ok(bits)
+}
+
+// Tablica bitów z JSON: tylko liczby 0 i 1
+fn json_bit_array(values: &[Value], field: &str) -> Result<PackedBits, String> {
+    let mut bits = PackedBits::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        match value.as_u64() {
+            Some(bit @ (0 | 1)) => bits.push(bit as u8),
+            _ => {
+                return Err(format!(
+                    "{}[{}] = {} nie jest bitem 0/1",
+                    field, index, value
+                ))
+            }
+        }
+    }
+    Ok(bits)
+}
+
+// Bajty rozpakowane do PackedBits, 8 bitów na bajt w kolejności msb_first
+fn bytes_to_bits(bytes: &[u8], msb_first: bool) -> PackedBits {
+    let mut bits = PackedBits::with_capacity(bytes.len() * 8);
+    bits.extend_from_bytes(bytes, msb_first);
+    bits
+}
+
+pub fn from_json(text: &[u8], msb_first: bool) -> Result<PackedBits, String> {
+    let value: Value =
+        serde_json::from_slice(text).map_err(|err| format!("niepoprawny JSON: {}", err))?;
+    if let Value::Array(values) = &value {
+        return json_bit_array(values, "bits");
+    }
+
+    for field in ["bits", "generated_bits"] {
+        if let Some(values) = value.get(field) {
+            let values = values
+                .as_array()
+                .ok_or_else(|| format!("pole {} nie jest tablicą", field))?;
+            return json_bit_array(values, field);
+        }
+    }
+
+    if let Some(encoded) = value.get("bits_base64") {
+        let encoded = encoded
+            .as_str()
+            .ok_or("pole bits_base64 nie jest napisem")?;
+        let msb_first = match value.get("bit_order").and_then(Value::as_str) {
+            Some("msb") => true,
+            Some("lsb") => false,
+            Some(other) => return Err(format!("nieznane bit_order: '{}'", other)),
+            None => msb_first,
+        };
+        let mut bits = bytes_to_bits(&from_base64(encoded.as_bytes())?, msb_first);
+        // n_bits odcina dopełnienie ostatniego bajtu
+        if let Some(n_bits) = value.get("n_bits").and_then(Value::as_u64) {
+            if n_bits > bits.len() as u64 {
+                return Err(format!(
+                    "n_bits = {} przekracza liczbę bitów w bits_base64 ({})",
+                    n_bits,
+                    bits.len()
+                ));
+            }
+            bits.truncate(n_bits as usize);
+        }
+        return Ok(bits);
+    }
+
+    Err("JSON nie zawiera pola bits, bits_base64 ani generated_bits".to_string())
+}
+
+pub fn detect_format(data: &[u8]) -> Format {
+    let mut text = significant(data).peekable();
+    match text.peek() {
+        Some(b'{') | Some(b'[') if from_json(data, true).is_ok() => Format::Json,
+        _ if data
+            .iter()
+            .all(|c| c.is_ascii_whitespace() || matches!(c, b'0' | b'1')) =>
+        {
+            Format::Ascii01
+        }
+        _ if data
+            .iter()
+            .all(|c| c.is_ascii_whitespace() || c.is_ascii_hexdigit()) =>
+        {
+            Format::Hex
+        }
+        _ => Format::Raw,
+    }
+}
+
+pub fn read_bits(data: &[u8], format: Format, msb_first: bool) -> Result<PackedBits, String> {
+    match format {
+        Format::Json => from_json(data, msb_first),
+        Format::Raw => Ok(bytes_to_bits(data, msb_first)),
+        Format::Hex => Ok(bytes_to_bits(&from_hex(data)?, msb_first)),
+        Format::Base64 => Ok(bytes_to_bits(&from_base64(data)?, msb_first)),
+        Format::Ascii01 => from_ascii01(data),
+    }
+}
@@ -0,0 +1,212 @@
+use std::io;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::primes::{gcd, prime_factors};
+use crate::stream::{stream_values, BitSink};
+
+/*
+LCG — konfigurowalny generator liniowy kongruencyjny, odpowiednik LCG.py.
+
+    x_{n+1} = (a * x_n + c) mod m
+
+Typ publiczny:
+    Lcg::new(seed, a, c, m)          -- parametry dowolne, 2 <= m <= 2^64
+    Lcg::next_value()                -- kolejny stan (to on jest wartością, jak w Pythonie)
+    Lcg::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                     -- strumieniowo do BitSink (stała pamięć)
+    hull_dobell_warnings(a, c, m)    -- opisy niespełnionych warunków pełnego okresu
+
+Arytmetyka: a, c i seed są redukowane modulo m, iloczyn a * x liczony jest na
+u128, więc moduł może mieć do 2^64 włącznie. Dla m będącego potęgą dwójki
+dzielenie zastępuje maska (szybka ścieżka, np. m = 2^31, 2^32, 2^64).
+
+Twierdzenie Hulla–Dobella: dla c != 0 okres wynosi m wtedy i tylko wtedy, gdy
+    1) gcd(c, m) = 1,
+    2) a - 1 jest podzielne przez każdy czynnik pierwszy m,
+    3) a - 1 jest podzielne przez 4, jeśli 4 dzieli m.
+Czynniki pierwsze m wyznacza primes::prime_factors. Niespełnione warunki nie
+blokują generacji — trafiają do pola "warnings" wyniku JSON, żeby dało się
+pokazać zachowanie złych zestawów parametrów na realnych próbkach.
+
+Funkcja publiczna:
+    lcg_bit_stream(nBits, bitsPerValue=bit_length(m), msbFirst=true, seed, a, c, m)
+        -> Result<(bity, czas), komunikat> — błąd dla złego m i bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości
+                                (domyślnie m.bit_length() jak w LCG.py, > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed, a, c, m            -- parametry jak w lcg_bit_stream(seed, a, c, m, ...)
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach (albo błąd parametrów);
+wynik jest bit w bit taki sam jak w LCG.py.
+*/
+
+// Domyślne parametry (GLIBC-like, jak w UniversalRNGAdapter i docstringu LCG.py)
+pub const DEFAULT_SEED: u64 = 123456789;
+pub const DEFAULT_A: u128 = 1103515245;
+pub const DEFAULT_C: u128 = 12345;
+pub const DEFAULT_M: u128 = 1 << 31;
+
+pub const MAX_MODULUS: u128 = 1 << 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lcg {
+    state: u64,
+    a: u64,
+    c: u64,
+    m: u128,
+    // m - 1, jeśli m jest potęgą dwójki
+    mask: Option<u64>,
+}
+
+// Liczba bitów potrzebna do zapisu m (int.bit_length() w Pythonie)
+pub fn bit_length(m: u128) -> usize {
+    (128 - m.leading_zeros()) as usize
+}
+
+// Domyślne bits_per_value jak w LCG.py: m.bit_length() (1 dla m <= 1)
+pub fn default_bits_per_value(m: u128) -> usize {
+    bit_length(m).max(1)
+}
+
+pub fn validate_parameters(m: u128) -> Result<(), String> {
+    if m < 2 {
+        return Err(format!("moduł m musi wynosić co najmniej 2 (podano {})", m));
+    }
+    if m > MAX_MODULUS {
+        return Err(format!("moduł m może wynosić najwyżej 2^64 (podano {})", m));
+    }
+    Ok(())
+}
+
+pub fn hull_dobell_warnings(a: u128, c: u128, m: u128) -> Vec<String> {
+    let a = a % m;
+    let c = c % m;
+    let mut warnings = Vec::new();
+
+    if c == 0 {
+        warnings.push(format!(
+            "c = 0 (generator multiplikatywny): okres jest krótszy niż m = {}, \
+             a stan 0 jest punktem stałym",
+            m
+        ));
+        return warnings;
+    }
+
+    if gcd(c, m) != 1 {
+        warnings.push(format!(
+            "Hull–Dobell: gcd(c, m) = {} != 1 — okres krótszy niż m",
+            gcd(c, m)
+        ));
+    }
+
+    // a - 1 modulo m (dla a = 0 to m - 1)
+    let a_minus_1 = (a + m - 1) % m;
+    let missing: Vec<String> = prime_factors(m)
+        .into_iter()
+        .filter(|&p| !a_minus_1.is_multiple_of(p as u128))
+        .map(|p| p.to_string())
+        .collect();
+    if !missing.is_empty() {
+        warnings.push(format!(
+            "Hull–Dobell: a - 1 nie jest podzielne przez czynniki pierwsze m: {} — okres krótszy niż m",
+            missing.join(", ")
+        ));
+    }
+
+    if m.is_multiple_of(4) && !a_minus_1.is_multiple_of(4) {
+        warnings.push(
+            "Hull–Dobell: 4 dzieli m, ale a - 1 nie jest podzielne przez 4 — okres krótszy niż m"
+                .to_string(),
+        );
+    }
+
+    warnings
+}
+
+impl Lcg {
+    pub fn new(seed: u128, a: u128, c: u128, m: u128) -> Result<Self, String> {
+        validate_parameters(m)?;
+        let mask = m.is_power_of_two().then(|| (m - 1) as u64);
+        Ok(Lcg {
+            state: (seed % m) as u64,
+            a: (a % m) as u64,
+            c: (c % m) as u64,
+            m,
+            mask,
+        })
+    }
+
+    pub fn modulus(&self) -> u128 {
+        self.m
+    }
+
+    pub fn next_value(&mut self) -> u64 {
+        self.state = match self.mask {
+            // m = 2^k: mnożenie modulo 2^64 i maska zamiast dzielenia
+            Some(mask) => self.a.wrapping_mul(self.state).wrapping_add(self.c) & mask,
+            None => ((self.a as u128 * self.state as u128 + self.c as u128) % self.m) as u64,
+        };
+        self.state
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(
+            n_bits,
+            bits_per_value,
+            msb_first,
+            || self.next_value(),
+            sink,
+        )
+    }
+}
+
+pub fn lcg_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u128,
+    a: u128,
+    c: u128,
+    m: u128,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let mut generator = Lcg::new(seed, a, c, m)?;
+    let bpv = bits_per_value.unwrap_or(default_bits_per_value(m));
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
@@ -0,0 +1,225 @@
+use serde_json::{json, Map, Value};
+
+use crate::bits::BitSequence;
+use crate::nist::round_python;
+
+/*
+Złożoność liniowa (długość najkrótszego LFSR generującego ciąg) algorytmem
+Berlekampa–Masseya na słowach u64 — zamiast berlekamp_massey z
+run_rng_test.py, który idzie bit po bicie po listach.
+
+    berlekamp_massey(bits)     -- złożoność liniowa L całego ciągu
+    complexity_profile(bits)   -- L i profil: punkty skoku (N, L_N)
+    report(bits, lanes)        -- raport JSON podkomendy rng linear-complexity
+
+Ciąg jest trzymany odwrócony (bit j słowa = s_(n-1-j)), więc rozbieżność
+d = s_N + sum c_i s_(N-i) to parzystość AND wielomianu połączeń C z 64-bitowym
+oknem ciągu, słowo po słowie, a aktualizacja C ^= B x^(N-m) to XOR
+przesuniętych słów. Koszt O(n^2 / 64) zamiast O(n^2); wynik jest ten sam co
+w Pythonie (nist::linear_complexity używa tej funkcji dla każdego bloku M).
+
+Dla ciągu losowego L_N trzyma się N/2 (odchylenie rzędu kilku bitów, profil
+rośnie schodkami). Ciąg z LFSR długości L (xorshift, młodsze bity LCG modulo
+2^k, bit k ma okres 2^(k+1)) zatrzymuje się na L. Gdy n >= 2L + PREDICTION_MARGIN,
+LFSR wyznaczony z pierwszych 2L bitów przewiduje resztę ciągu; dla ciągu
+losowego P(n - 2L_n >= 64) ~ 2^-64, więc raport oznacza go jako predictable.
+*/
+
+// Nadwyżka bitów ponad 2L potwierdzająca, że ciąg jest generowany przez LFSR
+pub const PREDICTION_MARGIN: usize = 64;
+
+// Liczba punktów profilu w raporcie (równo rozłożone N, ostatni = n)
+pub const PROFILE_POINTS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub n_bits: usize,
+    pub linear_complexity: usize,
+    // (N, L): po N bitach złożoność wzrasta do L; między punktami jest stała
+    pub jumps: Vec<(usize, usize)>,
+}
+
+impl Profile {
+    // L_N dla N <= n_bits
+    pub fn complexity_at(&self, n: usize) -> usize {
+        match self.jumps.partition_point(|&(at, _)| at <= n) {
+            0 => 0,
+            index => self.jumps[index - 1].1,
+        }
+    }
+
+    pub fn predictable(&self) -> bool {
+        self.n_bits >= 2 * self.linear_complexity + PREDICTION_MARGIN
+    }
+}
+
+// 64 bity ciągu od pozycji `offset`
+fn window(words: &[u64], offset: usize) -> u64 {
+    let (index, shift) = (offset / 64, offset % 64);
+    match shift {
+        0 => words[index],
+        _ => words[index] >> shift | words[index + 1] << (64 - shift),
+    }
+}
+
+// target ^= source * x^shift
+fn xor_shifted(target: &mut [u64], source: &[u64], shift: usize) {
+    let (offset, shift) = (shift / 64, shift % 64);
+    for (i, &word) in source.iter().enumerate() {
+        target[offset + i] ^= word << shift;
+        if shift > 0 {
+            target[offset + i + 1] ^= word >> (64 - shift);
+        }
+    }
+}
+
+// Berlekamp–Massey; on_jump(N, L) przy każdym wzroście L
+fn run<B: BitSequence + ?Sized>(bits: &B, mut on_jump: impl FnMut(usize, usize)) -> usize {
+    let n = bits.len();
+    // Zapas: okno czyta słowo dalej, przesunięte B może sięgnąć dwa słowa za n
+    let words = n / 64 + 3;
+    let mut reversed = vec![0u64; words];
+    for i in (0..n).filter(|&i| bits.bit(i) == 1) {
+        let j = n - 1 - i;
+        reversed[j / 64] |= 1 << (j % 64);
+    }
+
+    // C = B = 1, L = 0, m = -1 (trzymane jako m + 1)
+    let mut c = vec![0u64; words];
+    let mut b = vec![0u64; words];
+    let mut saved = vec![0u64; words];
+    c[0] = 1;
+    b[0] = 1;
+    let mut l = 0usize;
+    let mut b_degree = 0usize;
+    let mut m_next = 0usize;
+
+    for big_n in 0..n {
+        // d = sum_{i<=L} c_i s_(N-i), a s_(N-i) to bit (n-1-N)+i odwróconego ciągu
+        let offset = n - 1 - big_n;
+        let d = (0..=l / 64).fold(0u64, |acc, w| {
+            acc ^ (c[w] & window(&reversed, offset + 64 * w))
+        });
+        if d.count_ones() % 2 == 0 {
+            continue;
+        }
+
+        let grows = 2 * l <= big_n;
+        if grows {
+            saved[..=l / 64].copy_from_slice(&c[..=l / 64]);
+        }
+        xor_shifted(&mut c, &b[..=b_degree / 64], big_n + 1 - m_next);
+        if grows {
+            std::mem::swap(&mut b, &mut saved);
+            b_degree = l;
+            l = big_n + 1 - l;
+            m_next = big_n + 1;
+            on_jump(big_n + 1, l);
+        }
+    }
+    l
+}
+
+pub fn berlekamp_massey<B: BitSequence + ?Sized>(bits: &B) -> usize {
+    run(bits, |_, _| {})
+}
+
+pub fn complexity_profile<B: BitSequence + ?Sized>(bits: &B) -> Profile {
+    let mut jumps = Vec::new();
+    let linear_complexity = run(bits, |n, l| jumps.push((n, l)));
+    Profile {
+        n_bits: bits.len(),
+        linear_complexity,
+        jumps,
+    }
+}
+
+// Wartość oczekiwana L_n ciągu losowego (SP 800-22 §3.10)
+pub fn expected_complexity(n: usize) -> f64 {
+    let n_f = n as f64;
+    let sign = if (n + 1).is_multiple_of(2) { 1.0 } else { -1.0 };
+    // Dla n > 1024 składnik z 2^n i tak znika (powi przyjmuje i32)
+    n_f / 2.0 + (9.0 + sign) / 36.0 - (n_f / 3.0 + 2.0 / 9.0) / 2f64.powi(n.min(1024) as i32)
+}
+
+fn profile_json(profile: &Profile) -> Map<String, Value> {
+    let n = profile.n_bits;
+    let points: Vec<Value> = (1..=PROFILE_POINTS.min(n))
+        .map(|i| {
+            let at = n * i / PROFILE_POINTS.min(n);
+            json!([at, profile.complexity_at(at)])
+        })
+        .collect();
+    // Największe odchylenie |L_N - N/2| — wystarczy sprawdzić punkty skoku i tuż przed nimi
+    let deviation = |at: usize| profile.complexity_at(at) as f64 - at as f64 / 2.0;
+    let max_deviation = profile
+        .jumps
+        .iter()
+        .flat_map(|&(at, _)| [at - 1, at])
+        .chain([n])
+        .map(|at| (at, deviation(at)))
+        .fold((0, 0.0f64), |best, current| {
+            if current.1.abs() > best.1.abs() {
+                current
+            } else {
+                best
+            }
+        });
+
+    let mut map = Map::new();
+    map.insert("n_bits".to_string(), json!(n));
+    map.insert(
+        "linear_complexity".to_string(),
+        json!(profile.linear_complexity),
+    );
+    map.insert(
+        "expected_complexity".to_string(),
+        json!(round_python(expected_complexity(n), 6)),
+    );
+    map.insert("predictable".to_string(), json!(profile.predictable()));
+    map.insert("jumps".to_string(), json!(profile.jumps.len()));
+    map.insert(
+        "max_deviation".to_string(),
+        json!({"n_bits": max_deviation.0, "deviation": max_deviation.1}),
+    );
+    map.insert("profile".to_string(), json!(points));
+    map
+}
+
+/*
+Raport złożoności liniowej: profil całego ciągu, a dla lanes > 1 także
+każdego z `lanes` podciągów bitów o tej samej pozycji w wartości (co lanes-ty
+bit, od bitu lane) — tam wychodzą młodsze bity LCG i liniowe bity xorshift,
+które w całym ciągu giną między pozostałymi. passed = żaden ciąg nie jest
+predictable.
+*/
+pub fn report<B: BitSequence + ?Sized>(bits: &B, lanes: usize) -> Map<String, Value> {
+    let start = std::time::Instant::now();
+    let profile = complexity_profile(bits);
+    let mut passed = !profile.predictable();
+    let mut report = profile_json(&profile);
+
+    if lanes > 1 {
+        let lane_reports: Vec<Value> = (0..lanes)
+            .map(|lane| {
+                let lane_bits: Vec<u8> = (lane..bits.len())
+                    .step_by(lanes)
+                    .map(|i| bits.bit(i))
+                    .collect();
+                let lane_profile = complexity_profile(&lane_bits);
+                passed &= !lane_profile.predictable();
+                json!({
+                    "lane": lane,
+                    "n_bits": lane_profile.n_bits,
+                    "linear_complexity": lane_profile.linear_complexity,
+                    "predictable": lane_profile.predictable(),
+                })
+            })
+            .collect();
+        report.insert("lanes".to_string(), json!(lane_reports));
+    }
+
+    report.insert("passed".to_string(), json!(passed));
+    report.insert("time".to_string(), json!(start.elapsed().as_secs_f64()));
+    report
+}
@@ -0,0 +1,81 @@
+/*
+chacha20_rng — biblioteka generatora ChaCha20 CSPRNG.
+
+Moduły:
+    algorithm
+            -- rejestr generatorów wybieranych opcją --algorithm
+    awc     -- generator add-with-carry / subtract-with-borrow (opóźnienia r > s,
+               dowolna baza <= 2^64) seedowany jak w AWCG.py
+    bbs     -- Blum-Blum-Shub z modułem dowolnej wielkości (BigUint), kontrola
+               liczb pierwszych Bluma i gcd(seed, N), zgodny z BlumBlumShub.py
+    cli     -- wspólna warstwa CLI binarek rng i chacha20_rng (parse_args, run)
+    chacha  -- generator ChaCha20Generator (rand_core::RngCore + SeedableRng),
+               seedowanie (KDF) i chacha20_bit_stream
+    bits    -- ekstrakcja bitów z wartości (int_to_bits), operacja odwrotna
+               i tryby ekstrakcji (Extraction: truncate / contiguous)
+    fft     -- FFT w miejscu dowolnej długości (mixed radix, Bluestein) i widmo
+               ciągu rzeczywistego dla testu widmowego NIST
+    input   -- odczyt bitów z plików (raw, hex, base64, ascii01, JSON) dla
+               podkomendy analyze — odwrotność output
+    lcg     -- konfigurowalny LCG (dowolne a, c, m <= 2^64) z ostrzeżeniami
+               Hulla–Dobella, zgodny z LCG.py
+    lfsr    -- złożoność liniowa: Berlekamp–Massey na słowach u64, profil
+               długości LFSR i raport podkomendy linear-complexity
+    mt19937 -- Mersenne Twister MT19937 i MT19937-64 (init_genrand, init_by_array),
+               MT19937 zgodny z random.getrandbits z CPythona (PythonRNG.py)
+    nist    -- testy NIST SP 800-22 (15 testów) zgodne z _nist_*_test
+               z run_rng_test.py: ten sam wynik {passed, score, statistics}
+    output  -- serializacja wyniku: JSON {"bits": [...], "time": ...}, raw, hex,
+               base64, ascii01 (w pamięci i strumieniowo przez create_sink)
+    park_miller
+            -- Park–Miller "Minimal Standard" (mnożnik 16807, 48271 lub 69621)
+               liczony metodą Schrage'a, zgodny z Park_Miller.py
+    pcg32   -- generator PCG32 (XSH-RR) zgodny bit w bit z PCG32.py
+    splitmix64
+            -- generator SplitMix64 zgodny z SplitMix64.py (także KDF dla chacha)
+    xoshiro256
+            -- generator Xoshiro256** seedowany przez SplitMix64, jump/long_jump
+    primes  -- Miller–Rabin (u64 i BigUint) i rozkład na czynniki (Pollard rho)
+    stream  -- strumieniowa generacja o stałej pamięci (BitSink, stream_values)
+    system  -- systemowy CSPRNG (getrandom(2), fallback /dev/urandom) z tą samą
+               ekstrakcją bitów co ChaCha20, odpowiednik SystemRNG.py
+
+Binarki rng (src/bin/rng.rs) i chacha20_rng (main.rs) tylko wywołują cli::run,
+więc inne narzędzia, benchmarki i testy mogą używać generatora bezpośrednio,
+bez uruchamiania procesu i parsowania JSON-a.
+*/
+
+pub mod algorithm;
+pub mod awc;
+pub mod bbs;
+pub mod bits;
+pub mod chacha;
+pub mod cli;
+pub mod fft;
+pub mod input;
+pub mod lcg;
+pub mod lfsr;
+pub mod mt19937;
+pub mod nist;
+pub mod output;
+pub mod park_miller;
+pub mod pcg32;
+pub mod primes;
+pub mod splitmix64;
+pub mod stream;
+pub mod system;
+pub mod xoshiro256;
+
+pub use algorithm::Algorithm;
+pub use awc::{awc_bit_stream, Awc, AwcVariant};
+pub use bbs::{bbs_bit_stream, Bbs};
+pub use bits::{bits_to_int, int_to_bits, Extraction};
+pub use chacha::{chacha20_bit_stream, derive_key, ChaCha20Generator, KEY_LEN, NONCE_LEN};
+pub use lcg::{lcg_bit_stream, Lcg};
+pub use mt19937::{mt19937_64_bit_stream, mt19937_bit_stream, Mt19937, Mt19937_64, MtInit};
+pub use nist::{NistTest, TestResult};
+pub use park_miller::{park_miller_bit_stream, ParkMiller};
+pub use pcg32::{pcg32_bit_stream, Pcg32};
+pub use splitmix64::{splitmix64_bit_stream, SplitMix64};
+pub use system::{system_bit_stream, SystemRng};
+pub use xoshiro256::{xoshiro256_bit_stream, Xoshiro256StarStar};
@@ -1,143 +1,81 @@
-use chacha20::ChaCha20;
-use chacha20::cipher::{KeyIvInit, StreamCipher};
-use std::time::Instant;
-use serde_json::json;
+use chacha20_rng::cli;
 
 /*
-ChaCha20 CSPRNG — Algorytm szyfrowania strumienia do generacji liczb losowych.
-
-Funkcja publiczna:
-    chacha20_bit_stream(nBits, bitsPerValue=32, msbFirst=true) -> {"bits": [...], "time": 0.001234}
-
-Parametry:
-    nBits : usize            -- liczba bitów do zwrócenia
-    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 32)
-    msbFirst : bool          -- True: MSB-first, False: LSB-first
-
-Zwraca JSON z tablicą bitów binarnych i czasem wykonania w sekundach.
+chacha20_rng — warstwa CLI nad biblioteką chacha20_rng.
+
+Cała logika jest w bibliotece (cli::run) i jest wspólna z binarką rng
+(src/bin/rng.rs); ta binarka zostaje dla zgodności z tester.py i istniejącymi
+skryptami. Parsuje argumenty, wyznacza klucz i nonce i strumieniowo przepuszcza
+strumień klucza przez ekstrakcję bitów do serializera (output::create_sink),
+więc pamięć jest stała nawet dla dziesiątek miliardów bitów. Wynik trafia
+na stdout albo do pliku --output:
+    --format json (domyślnie) -> {"bits": [...], "time": 0.001234, "seed": 42, "key": "...", "nonce": "..."}
+    --format raw              -> surowe bajty, 8 bitów na bajt w kolejności msb_first
+    --format hex / base64     -> te same bajty jako tekst, linie po --width znaków
+    --format ascii01          -> znaki '0'/'1' (format wejściowy NIST STS assess)
+    --json-bits base64        -> JSON z polem "bits_base64" zamiast tablicy "bits"
+
+Seedowanie (opcje linii poleceń, pełna lista w cli.rs):
+    --seed <u64>             -- klucz wyprowadzany z seeda przez KDF (chacha::derive_key)
+    --key <64 znaki hex>     -- pełny klucz 256-bit podany wprost (wyklucza --seed)
+    --nonce <24 znaki hex>   -- jawny nonce (domyślnie same zera)
+Bez --seed i --key używany jest klucz zerowy (dotychczasowe zachowanie).
+
+--algorithm pcg32 przełącza na PCG32 (pcg32.rs): --seed to initstate, a --seq
+numer strumienia, oba domyślnie 1 jak w PCG32.py; wynik jest bit w bit taki
+sam jak pcg32_bit_stream((seed, seq), nBits, bitsPerValue, msbFirst).
+--algorithm splitmix64 wybiera SplitMix64 (splitmix64.rs): --seed to stan
+początkowy (domyślnie 1), bits_per_value domyślnie 64, wynik jak
+splitmix64_bit_stream(seed, nBits, bitsPerValue, msbFirst).
+Każdy JSON zawiera pole "algorithm".
 */
 
-fn int_to_bits(value: u64, bits: usize, msb_first: bool) -> Vec<u8> {
-    if bits == 0 {
-        return Vec::new();
-    }
-
-    let mut result = Vec::with_capacity(bits);
-    
-    if msb_first {
-        for i in (0..bits).rev() {
-            result.push(((value >> i) & 1) as u8);
-        }
-    } else {
-        for i in 0..bits {
-            result.push(((value >> i) & 1) as u8);
-        }
+fn main() {
+    if let Err(message) = cli::run("chacha20_rng", std::env::args().skip(1)) {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
     }
-
-    result
 }
 
-fn chacha20_bit_stream(
-    n_bits: usize,
-    bits_per_value: Option<usize>,
-    msb_first: bool,
-) -> (Vec<u8>, f64) {
-    let start = Instant::now();
-
-    if n_bits == 0 {
-        return (Vec::new(), 0.0);
-    }
-
-    let bpv = bits_per_value.unwrap_or(32);
-    let num_bytes = (bpv + 7) / 8;
-
-    // Klucz i nonce dla ChaCha20 (mogą być dowolne dla CSPRNG)
-    let key = [0u8; 32]; // 256-bit key
-    let nonce = [0u8; 12]; // 96-bit nonce
-    
-    let mut cipher = ChaCha20::new(key.as_ref().into(), nonce.as_ref().into());
-    
-    let mut output = Vec::new();
-    let mut buffer = vec![0u8; num_bytes];
-
-    while output.len() < n_bits {
-        // Generuj liczby losowe
-        cipher.apply_keystream(&mut buffer);
-
-        // Konwertuj bytes do u64 (big-endian)
-        let mut val: u64 = 0;
-        for byte in &buffer {
-            val = (val << 8) | (*byte as u64);
-        }
-
-        // Maskuj do bitsPerValue
-        let max_bits = (num_bytes * 8) as u32;
-        if (bpv as u32) < max_bits {
-            val = val & ((1u64 << bpv) - 1);
-        }
-
-        let bits = int_to_bits(val, bpv, msb_first);
-        let remaining = n_bits - output.len();
-
-        if remaining >= bits.len() {
-            output.extend(&bits);
-        } else {
-            output.extend(&bits[..remaining]);
-        }
-    }
-
-    let elapsed = start.elapsed().as_secs_f64();
-    (output, elapsed)
-}
+/*
+Krótki przykład użycia z terminala:
 
-fn main() {
-    // Parsuj argumenty z linii poleceń
-    let args: Vec<String> = std::env::args().collect();
+# Domyślnie: 200 bitów, 32 bity na wartość, MSB-first, klucz zerowy
+.\chacha20_rng.exe
 
-    let mut n_bits = 200usize;
-    let mut bits_per_value = 32usize;
-    let mut msb_first = true;
+# Pozycyjnie (jak tester.py): seed=42 nBits=100 bitsPerValue=16 msbFirst=true
+.\chacha20_rng.exe 42 100 16 true
 
-    if args.len() > 1 {
-        if let Ok(n) = args[1].parse::<usize>() {
-            n_bits = n;
-        }
-    }
+# Te same parametry opcjami nazwanymi
+.\chacha20_rng.exe --seed 42 --bits 100 --bits-per-value 16
 
-    if args.len() > 2 {
-        if let Ok(n) = args[2].parse::<usize>() {
-            bits_per_value = n;
-        }
-    }
+# PCG32 zamiast ChaCha20: initstate=42, seq=54
+.\chacha20_rng.exe --algorithm pcg32 --seed 42 --seq 54 --bits 200
 
-    if args.len() > 3 {
-        msb_first = args[3].to_lowercase() != "false";
-    }
+# SplitMix64, domyślnie 64 bity na wartość
+.\chacha20_rng.exe --algorithm splitmix64 --seed 123456789 --bits 200
 
-    // Generuj bity
-    let (bits, elapsed) = chacha20_bit_stream(n_bits, Some(bits_per_value), msb_first);
+# Z jawnym kluczem i nonce, LSB-first
+.\chacha20_rng.exe --key 000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f --nonce 000000090000004a00000000 --bits 100 --lsb-first
 
-    // Konwertuj na liczby (0 i 1)
-    let bits_as_ints: Vec<i32> = bits.iter().map(|&b| b as i32).collect();
+# Surowe bajty do pliku (np. dla PractRand / dieharder)
+.\chacha20_rng.exe --seed 42 --bits 80000000 --format raw --output chacha.bin
 
-    // Utwórz JSON
-    let result = json!({
-        "bits": bits_as_ints,
-        "time": elapsed
-    });
+# Strumieniowo do PractRand (stała pamięć niezależnie od liczby bitów)
+chacha20_rng --seed 42 --bits 1e11 --format raw | RNG_test stdin
 
-    println!("{}", result.to_string());
-}
+# 12-bitowe wartości bez marnowania bitów strumienia klucza
+.\chacha20_rng.exe --seed 42 --bits 1000 --bits-per-value 12 --extraction contiguous
 
-/*
-Krótki przykład użycia z terminala:
+# Plik ascii01 dla NIST STS assess, 1000 znaków w linii
+.\chacha20_rng.exe --seed 42 --bits 1000000 --format ascii01 --width 1000 --output chacha.txt
 
-# Domyślnie: 200 bitów, 32 bity na wartość, MSB-first
-.\chacha20_rng.exe
+# JSON z bitami spakowanymi w base64 zamiast tablicy
+.\chacha20_rng.exe --seed 42 --bits 1000000 --json-bits base64
 
-# Z parametrami: nBits=100 bitsPerValue=16 msbFirst=true
-.\chacha20_rng.exe 100 16 true
+# Testy NIST na bitach z pliku (raw, hex, base64, ascii01 albo JSON {"bits": [...]})
+.\chacha20_rng.exe analyze trng.bin --tests monobit,runs,serial
 
-# Wynik: JSON z bitami i czasem wykonania
-{"bits":[1,0,1,1,0,1,0,1,...],"time":0.001234}
+# Wynik: JSON z bitami, czasem wykonania oraz użytym kluczem i nonce
+{"bits":[1,0,1,1,0,1,0,1,...],"time":0.001234,"seed":42,"key":"...","nonce":"000000000000000000000000"}
 */
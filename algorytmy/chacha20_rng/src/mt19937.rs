@@ -0,0 +1,536 @@
+use rand_core::{impls, Error, RngCore, SeedableRng};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::bits::{bytes_to_bits_into, check_bits_per_value, int_to_bits_into};
+use crate::stream::{stream_bits, BitSink};
+
+/*
+Mersenne Twister — MT19937 (32-bit, mt19937ar.c) i MT19937-64 (mt19937-64.c)
+Matsumoto i Nishimury, natywny odpowiednik PythonRNG.py (moduł random).
+
+Typ publiczny:
+    Mt19937::new(seed)                 -- init_genrand(seed), seed: u32
+    Mt19937::from_key(key)             -- init_by_array(key), key: &[u32]
+    Mt19937::from_python_seed(seed)    -- jak random.Random(seed) w CPythonie
+    Mt19937::next_u32()                -- genrand_int32()
+    Mt19937_64::new(seed)              -- init_genrand64(seed), seed: u64
+    Mt19937_64::from_key(key)          -- init_by_array64(key), key: &[u64]
+    Mt19937_64::next_u64()             -- genrand64_int64()
+    {Mt19937, Mt19937_64}::seeded(seed, init)
+                                       -- seed z CLI wybraną inicjalizacją (MtInit)
+    {Mt19937, Mt19937_64}::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                       -- strumieniowo do BitSink (stała pamięć)
+
+CPython dla seeda całkowitego wywołuje init_by_array z kluczem złożonym
+z 32-bitowych słów |seed| od najmłodszego (seed 0 -> klucz [0]), a
+getrandbits(k) bierze k <= 32 najstarszych bitów jednego słowa; dla k > 32
+łączy słowa od najmłodszego, a ostatnie przesuwa w prawo o brakujące bity.
+Mt19937 robi to samo, więc mt19937_bit_stream daje bit w bit to samo co
+python_random_bit_stream(seed, nBits, bitsPerValue, msbFirst). MT19937-64
+wyciąga bity analogicznie, ze słów 64-bitowych.
+
+Domyślna inicjalizacja: dla MT19937 init_by_array (zgodność z Pythonem), dla
+MT19937-64 init_genrand64 (zgodność z std::mt19937_64 z C++). Domyślny seed
+5489 to seed implementacji referencyjnych i std::mt19937.
+
+Funkcje publiczne:
+    mt19937_bit_stream(nBits, bitsPerValue=32, msbFirst=true, seed, init)
+                                       -> (bity, czas) albo błąd seeda lub bitsPerValue = 0
+    mt19937_64_bit_stream(nBits, bitsPerValue=64, msbFirst=true, seed, init)
+                                       -> (bity, czas) albo błąd bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (dowolna
+                                szerokość, jak getrandbits; > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed : u64               -- ziarno (dla MT19937 z init_genrand <= 2^32 - 1)
+    init : MtInit            -- init_by_array albo init_genrand
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach.
+*/
+
+pub const DEFAULT_SEED: u64 = 5489;
+pub const DEFAULT_BITS_PER_VALUE: usize = 32;
+pub const DEFAULT_BITS_PER_VALUE_64: usize = 64;
+pub const DEFAULT_INIT: MtInit = MtInit::InitByArray;
+pub const DEFAULT_INIT_64: MtInit = MtInit::InitGenrand;
+
+// Parametry MT19937 z mt19937ar.c
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7fff_ffff;
+
+// Parametry MT19937-64 z mt19937-64.c
+const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A_64: u64 = 0xb502_6f5a_a966_19e9;
+const UPPER_MASK_64: u64 = 0xffff_ffff_8000_0000;
+const LOWER_MASK_64: u64 = 0x7fff_ffff;
+
+// Stała seed init_genrand w init_by_array / init_by_array64
+const ARRAY_INIT_SEED: u64 = 19650218;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MtInit {
+    InitByArray,
+    InitGenrand,
+}
+
+impl FromStr for MtInit {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "init_by_array" | "init-by-array" | "array" => Ok(MtInit::InitByArray),
+            "init_genrand" | "init-genrand" | "genrand" => Ok(MtInit::InitGenrand),
+            _ => Err(format!(
+                "nieznana inicjalizacja MT: '{}' (dostępne: init_by_array, init_genrand)",
+                text
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MtInit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MtInit::InitByArray => "init_by_array",
+            MtInit::InitGenrand => "init_genrand",
+        };
+        f.write_str(name)
+    }
+}
+
+// Klucz init_by_array, którego CPython używa dla random.Random(seed)
+pub fn python_key(seed: u64) -> Vec<u32> {
+    let (low, high) = (seed as u32, (seed >> 32) as u32);
+    if high == 0 {
+        vec![low]
+    } else {
+        vec![low, high]
+    }
+}
+
+// init_genrand przyjmuje tylko 32-bitowy seed — większego nie obcinamy po cichu
+pub fn validate_genrand_seed(seed: u64) -> Result<u32, String> {
+    u32::try_from(seed).map_err(|_| {
+        format!(
+            "seed MT19937 dla init_genrand musi mieścić się w 32 bitach (podano {})",
+            seed
+        )
+    })
+}
+
+/*
+Bity jednej wartości jak random.getrandbits(bits): kolejne słowa generatora
+(word_bits = 32 albo 64) to cyfry liczby od najmłodszej, a z ostatniego słowa
+zostają jego najstarsze bity.
+*/
+fn getrandbits_into<F>(
+    mut next_word: F,
+    word_bits: usize,
+    bits: usize,
+    msb_first: bool,
+    out: &mut Vec<u8>,
+) where
+    F: FnMut() -> u64,
+{
+    if bits == 0 {
+        return;
+    }
+    if bits <= word_bits {
+        let value = next_word() >> (word_bits - bits);
+        int_to_bits_into(value, bits, msb_first, out);
+        return;
+    }
+
+    // Szersze wartości: słowa zapisane big-endian, najmłodsze na końcu
+    let word_bytes = word_bits / 8;
+    let words = bits.div_ceil(word_bits);
+    let mut bytes = vec![0u8; words * word_bytes];
+    for index in 0..words {
+        let mut word = next_word();
+        let remaining = bits - index * word_bits;
+        if remaining < word_bits {
+            word >>= word_bits - remaining;
+        }
+        let end = bytes.len() - index * word_bytes;
+        bytes[end - word_bytes..end].copy_from_slice(&word.to_be_bytes()[8 - word_bytes..]);
+    }
+    bytes_to_bits_into(&bytes, bits, msb_first, out);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mt19937 {
+    mt: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    pub fn new(seed: u32) -> Self {
+        let mut mt = [0u32; N];
+        mt[0] = seed;
+        for i in 1..N {
+            mt[i] = 1812433253u32
+                .wrapping_mul(mt[i - 1] ^ (mt[i - 1] >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { mt, index: N }
+    }
+
+    // Pusty klucz jest traktowany jak [0] (CPython zawsze podaje co najmniej jedno słowo)
+    pub fn from_key(key: &[u32]) -> Self {
+        let key = if key.is_empty() { &[0u32][..] } else { key };
+        let mut generator = Self::new(ARRAY_INIT_SEED as u32);
+        let mt = &mut generator.mt;
+
+        let (mut i, mut j) = (1usize, 0usize);
+        for _ in 0..N.max(key.len()) {
+            mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                mt[0] = mt[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 30)).wrapping_mul(1566083941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                mt[0] = mt[N - 1];
+                i = 1;
+            }
+        }
+        // Najstarszy bit gwarantuje niezerowy stan początkowy
+        mt[0] = 0x8000_0000;
+        generator
+    }
+
+    pub fn from_python_seed(seed: u64) -> Self {
+        Self::from_key(&python_key(seed))
+    }
+
+    pub fn seeded(seed: u64, init: MtInit) -> Result<Self, String> {
+        match init {
+            MtInit::InitByArray => Ok(Self::from_python_seed(seed)),
+            MtInit::InitGenrand => Ok(Self::new(validate_genrand_seed(seed)?)),
+        }
+    }
+
+    // Wyznacza naraz kolejne N słów stanu
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.mt[i] & UPPER_MASK) | (self.mt[(i + 1) % N] & LOWER_MASK);
+            let mut next = self.mt[(i + M) % N] ^ (y >> 1);
+            if y & 1 != 0 {
+                next ^= MATRIX_A;
+            }
+            self.mt[i] = next;
+        }
+        self.index = 0;
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let mut y = self.mt[self.index];
+        self.index += 1;
+
+        // Tempering
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c_5680;
+        y ^= (y << 15) & 0xefc6_0000;
+        y ^ (y >> 18)
+    }
+
+    // Bity kolejnej wartości jak random.getrandbits(bits_per_value)
+    pub fn next_bits(&mut self, bits_per_value: usize, msb_first: bool, out: &mut Vec<u8>) {
+        getrandbits_into(
+            || self.next_u32() as u64,
+            32,
+            bits_per_value,
+            msb_first,
+            out,
+        );
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_bits(
+            n_bits,
+            |batch| self.next_bits(bits_per_value, msb_first, batch),
+            sink,
+        )
+    }
+}
+
+impl RngCore for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        Mt19937::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937 {
+    // Seed init_genrand zapisany little-endian
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u32::from_le_bytes(seed))
+    }
+
+    // Tak jak random.Random(state) w CPythonie
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_python_seed(state)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mt19937_64 {
+    mt: [u64; NN],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    pub fn new(seed: u64) -> Self {
+        let mut mt = [0u64; NN];
+        mt[0] = seed;
+        for i in 1..NN {
+            mt[i] = 6364136223846793005u64
+                .wrapping_mul(mt[i - 1] ^ (mt[i - 1] >> 62))
+                .wrapping_add(i as u64);
+        }
+        Mt19937_64 { mt, index: NN }
+    }
+
+    // Pusty klucz jest traktowany jak [0], jak w Mt19937::from_key
+    pub fn from_key(key: &[u64]) -> Self {
+        let key = if key.is_empty() { &[0u64][..] } else { key };
+        let mut generator = Self::new(ARRAY_INIT_SEED);
+        let mt = &mut generator.mt;
+
+        let (mut i, mut j) = (1usize, 0usize);
+        for _ in 0..NN.max(key.len()) {
+            mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 62)).wrapping_mul(3935559000370003845))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= NN {
+                mt[0] = mt[NN - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..NN - 1 {
+            mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 62)).wrapping_mul(2862933555777941757))
+                .wrapping_sub(i as u64);
+            i += 1;
+            if i >= NN {
+                mt[0] = mt[NN - 1];
+                i = 1;
+            }
+        }
+        mt[0] = 1 << 63;
+        generator
+    }
+
+    pub fn seeded(seed: u64, init: MtInit) -> Self {
+        match init {
+            MtInit::InitByArray => Self::from_key(&[seed]),
+            MtInit::InitGenrand => Self::new(seed),
+        }
+    }
+
+    // Wyznacza naraz kolejne NN słów stanu
+    fn twist(&mut self) {
+        for i in 0..NN {
+            let x = (self.mt[i] & UPPER_MASK_64) | (self.mt[(i + 1) % NN] & LOWER_MASK_64);
+            let mut next = self.mt[(i + MM) % NN] ^ (x >> 1);
+            if x & 1 != 0 {
+                next ^= MATRIX_A_64;
+            }
+            self.mt[i] = next;
+        }
+        self.index = 0;
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+        let mut x = self.mt[self.index];
+        self.index += 1;
+
+        // Tempering
+        x ^= (x >> 29) & 0x5555_5555_5555_5555;
+        x ^= (x << 17) & 0x71d6_7fff_eda6_0000;
+        x ^= (x << 37) & 0xfff7_eee0_0000_0000;
+        x ^ (x >> 43)
+    }
+
+    // Bity kolejnej wartości, jak getrandbits, ale ze słów 64-bitowych
+    pub fn next_bits(&mut self, bits_per_value: usize, msb_first: bool, out: &mut Vec<u8>) {
+        getrandbits_into(|| self.next_u64(), 64, bits_per_value, msb_first, out);
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_bits(
+            n_bits,
+            |batch| self.next_bits(bits_per_value, msb_first, batch),
+            sink,
+        )
+    }
+}
+
+impl RngCore for Mt19937_64 {
+    // Starsze 32 bity, jak w xoshiro256**
+    fn next_u32(&mut self) -> u32 {
+        (Mt19937_64::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Mt19937_64::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937_64 {
+    // Seed init_genrand64 zapisany little-endian
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    // Tak jak std::mt19937_64(state) w C++
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+pub fn mt19937_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u64,
+    init: MtInit,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let mut generator = Mt19937::seeded(seed, init)?;
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
+
+pub fn mt19937_64_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u64,
+    init: MtInit,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE_64);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut generator = Mt19937_64::seeded(seed, init);
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
@@ -0,0 +1,1081 @@
+use libm::erfc;
+use serde_json::{json, Map, Value};
+use std::f64::consts::LN_2;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::bits::{bit_chunks, BitSequence};
+use crate::fft::real_spectrum_magnitudes;
+pub use crate::lfsr::berlekamp_massey;
+
+/*
+Testy NIST SP 800-22 — natywny odpowiednik metod _nist_*_test z
+backend/io_rng/core/use_cases/run_rng_test.py (RunRNGTestUseCase).
+
+Każdy test zwraca TestResult o tym samym kształcie co słownik z Pythona:
+
+    {"passed": bool, "score": float, "statistics": {...}}
+
+z tymi samymi kluczami w statistics, tymi samymi komunikatami błędów
+(po angielsku, jak w backendzie) i tym samym zaokrąglaniem (round_python),
+więc use case w Django może podmienić implementację bez zmian w bazie i we
+froncie. Dodatkowo TestResult::p_values przechowuje surowe, niezaokrąglone
+p-wartości (dla serial dwie, dla random excursions variant osiemnaście).
+
+Wzory są przeniesione wiernie, łącznie z uproszczeniami backendu (np. p-wartość
+erfc(sqrt(chi^2 / 2)) zamiast igamc albo średnie chi^2 w random excursions),
+tak aby p-wartości zgadzały się z Pythonem do 1e-9 (tests/nist.rs). Zmienia się
+tylko koszt: wzorce w approximate entropy, serial i universal są liczone na
+indeksach całkowitych zamiast krotek, rangi macierzy na słowach u32,
+a Berlekamp–Massey w linear complexity na słowach u64 (lfsr.rs).
+Test DFT zamiast dft() z Pythona, liczonego wprost w O(n^2), używa FFT ciągu
+rzeczywistego (fft.rs) dla każdego n do DFT_MAX_BITS = 2^30. Bufory FFT
+mieszczą się w DFT_MEMORY = 1 GiB niezależnie od n (widmo jest liczone
+warstwami, a moduły zliczane w locie); n o dużym czynniku pierwszym płaci za
+to czasem (fft::SpectrumPlan::Chirp). Na danych e, pi i sqrt(2) z SP 800-22
+daje p-wartości z dodatku B.
+
+Typ publiczny:
+    NistTest                  -- nazwa testu jak test_name w backendzie
+                                 ("nist_monobit", ..., także bez prefiksu nist_)
+    NistTest::run(bits)       -- test z domyślnymi parametrami backendu
+    TestResult::to_json()     -- słownik {passed, score, statistics}
+    TestResult::report_entry(test, alpha)
+                              -- pozycja raportu: statystyka, p-wartość i wynik
+                                 przy progu alpha
+    report(bits, tests, alpha) -- raport dla wybranych testów (rng test)
+
+Funkcje publiczne (bity 0/1, parametry domyślne jak w Pythonie):
+    monobit(bits)                          block_frequency(bits, blockSize=128)
+    runs(bits)                             longest_run(bits)
+    cumulative_sums(bits)                  approximate_entropy(bits, m=10)
+    matrix_rank(bits)                      dft(bits)
+    non_overlapping_template(bits, template=000000001)
+    overlapping_template(bits)             universal(bits)
+    linear_complexity(bits, M=500)         serial(bits, m=16)
+    random_excursions(bits)                random_excursions_variant(bits)
+
+Test przechodzi, gdy p-wartość (każda z nich) >= THRESHOLD = 0.01.
+*/
+
+pub const THRESHOLD: f64 = 0.01;
+
+pub const DEFAULT_BLOCK_SIZE: usize = 128;
+pub const DEFAULT_ENTROPY_M: usize = 10;
+pub const DEFAULT_TEMPLATE: [u8; 9] = [0, 0, 0, 0, 0, 0, 0, 0, 1];
+pub const DEFAULT_LINEAR_COMPLEXITY_M: usize = 500;
+pub const DEFAULT_SERIAL_M: usize = 16;
+// Fragment ciągu przeglądany naraz w testach liczonych bit po bicie
+const SCAN_CHUNK: usize = 1 << 16;
+// Górna granica n dla dft
+pub const DFT_MAX_BITS: usize = 1 << 30;
+// Budżet buforów FFT w dft (fft::SpectrumPlan), niezależny od n
+pub const DFT_MEMORY: u64 = 1 << 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NistTest {
+    Monobit,
+    BlockFrequency,
+    Runs,
+    LongestRun,
+    CumulativeSums,
+    ApproximateEntropy,
+    MatrixRank,
+    Dft,
+    NonOverlappingTemplate,
+    OverlappingTemplate,
+    Universal,
+    LinearComplexity,
+    Serial,
+    RandomExcursions,
+    RandomExcursionsVariant,
+}
+
+impl NistTest {
+    // Kolejność jak w _perform_statistical_test
+    pub const ALL: &'static [NistTest] = &[
+        NistTest::Monobit,
+        NistTest::BlockFrequency,
+        NistTest::Runs,
+        NistTest::LongestRun,
+        NistTest::CumulativeSums,
+        NistTest::ApproximateEntropy,
+        NistTest::MatrixRank,
+        NistTest::Dft,
+        NistTest::NonOverlappingTemplate,
+        NistTest::OverlappingTemplate,
+        NistTest::Universal,
+        NistTest::LinearComplexity,
+        NistTest::Serial,
+        NistTest::RandomExcursions,
+        NistTest::RandomExcursionsVariant,
+    ];
+
+    // Nazwa jak test_name w backendzie
+    pub fn name(&self) -> &'static str {
+        match self {
+            NistTest::Monobit => "nist_monobit",
+            NistTest::BlockFrequency => "nist_block_frequency",
+            NistTest::Runs => "nist_runs",
+            NistTest::LongestRun => "nist_longest_run",
+            NistTest::CumulativeSums => "nist_cumulative_sums",
+            NistTest::ApproximateEntropy => "nist_approximate_entropy",
+            NistTest::MatrixRank => "nist_matrix_rank",
+            NistTest::Dft => "nist_dft",
+            NistTest::NonOverlappingTemplate => "nist_non_overlapping_template",
+            NistTest::OverlappingTemplate => "nist_overlapping_template",
+            NistTest::Universal => "nist_universal",
+            NistTest::LinearComplexity => "nist_linear_complexity",
+            NistTest::Serial => "nist_serial",
+            NistTest::RandomExcursions => "nist_random_excursions",
+            NistTest::RandomExcursionsVariant => "nist_random_excursions_variant",
+        }
+    }
+
+    pub fn run<B: BitSequence + ?Sized>(&self, bits: &B) -> TestResult {
+        match self {
+            NistTest::Monobit => monobit(bits),
+            NistTest::BlockFrequency => block_frequency(bits, DEFAULT_BLOCK_SIZE),
+            NistTest::Runs => runs(bits),
+            NistTest::LongestRun => longest_run(bits),
+            NistTest::CumulativeSums => cumulative_sums(bits),
+            NistTest::ApproximateEntropy => approximate_entropy(bits, DEFAULT_ENTROPY_M),
+            NistTest::MatrixRank => matrix_rank(bits),
+            NistTest::Dft => dft(bits),
+            NistTest::NonOverlappingTemplate => non_overlapping_template(bits, &DEFAULT_TEMPLATE),
+            NistTest::OverlappingTemplate => overlapping_template(bits),
+            NistTest::Universal => universal(bits),
+            NistTest::LinearComplexity => linear_complexity(bits, DEFAULT_LINEAR_COMPLEXITY_M),
+            NistTest::Serial => serial(bits, DEFAULT_SERIAL_M),
+            NistTest::RandomExcursions => random_excursions(bits),
+            NistTest::RandomExcursionsVariant => random_excursions_variant(bits),
+        }
+    }
+}
+
+impl FromStr for NistTest {
+    type Err = String;
+
+    // Przyjmuje nazwę z backendu albo bez prefiksu "nist_", z '-' albo '_'
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let normalized = text.trim().to_lowercase().replace('-', "_");
+        let short = normalized.strip_prefix("nist_").unwrap_or(&normalized);
+        NistTest::ALL
+            .iter()
+            .copied()
+            .find(|test| &test.name()["nist_".len()..] == short)
+            .ok_or_else(|| {
+                let names: Vec<&str> = NistTest::ALL
+                    .iter()
+                    .map(|test| &test.name()["nist_".len()..])
+                    .collect();
+                format!("nieznany test: '{}' (dostępne: {})", text, names.join(", "))
+            })
+    }
+}
+
+impl fmt::Display for NistTest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub passed: bool,
+    pub score: f64,
+    // Surowe p-wartości i statystyki, z których je policzono (po jednej
+    // na p-wartość); puste, gdy test zwrócił błąd
+    pub p_values: Vec<f64>,
+    pub test_statistics: Vec<f64>,
+    pub statistics: Map<String, Value>,
+}
+
+impl TestResult {
+    // Jak {'passed': False, 'score': 0.0, 'statistics': {'error': ...}} w Pythonie
+    fn error(message: &str, extra: Map<String, Value>) -> Self {
+        let mut statistics = Map::new();
+        statistics.insert("error".to_string(), json!(message));
+        statistics.extend(extra);
+        TestResult {
+            passed: false,
+            score: 0.0,
+            p_values: Vec::new(),
+            test_statistics: Vec::new(),
+            statistics,
+        }
+    }
+
+    // Wynik z jedną p-wartością: passed = p >= 0.01, score = min(1, p)
+    fn from_p_value(p_value: f64, statistic: f64, statistics: Map<String, Value>) -> Self {
+        TestResult {
+            passed: p_value >= THRESHOLD,
+            score: round_python(p_value.min(1.0), 4),
+            p_values: vec![p_value],
+            test_statistics: vec![statistic],
+            statistics,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "passed": self.passed,
+            "score": self.score,
+            "statistics": self.statistics,
+        })
+    }
+
+    /*
+    Pozycja raportu (report): wynik przy progu `alpha` zamiast stałego 0.01.
+    statistic i p_value dotyczą najmniejszej p-wartości, czyli tej, która
+    decyduje o wyniku; testy z kilkoma p-wartościami (serial, random excursions
+    variant) dopisują też pełne listy p_values i test_statistics.
+    */
+    pub fn report_entry(&self, test: NistTest, alpha: f64) -> Value {
+        let mut entry = Map::new();
+        entry.insert("test".to_string(), json!(test.name()));
+
+        let deciding =
+            (0..self.p_values.len()).min_by(|&a, &b| self.p_values[a].total_cmp(&self.p_values[b]));
+        match deciding {
+            Some(index) => {
+                entry.insert(
+                    "passed".to_string(),
+                    json!(self.p_values.iter().all(|&p| p >= alpha)),
+                );
+                entry.insert("statistic".to_string(), json!(self.test_statistics[index]));
+                entry.insert("p_value".to_string(), json!(self.p_values[index]));
+                if self.p_values.len() > 1 {
+                    entry.insert("p_values".to_string(), json!(self.p_values));
+                    entry.insert("test_statistics".to_string(), json!(self.test_statistics));
+                }
+            }
+            None => {
+                entry.insert("passed".to_string(), json!(false));
+                entry.insert("statistic".to_string(), Value::Null);
+                entry.insert("p_value".to_string(), Value::Null);
+                if let Some(error) = self.statistics.get("error") {
+                    entry.insert("error".to_string(), error.clone());
+                }
+            }
+        }
+        entry.insert("details".to_string(), json!(self.statistics));
+        Value::Object(entry)
+    }
+}
+
+/*
+Raport dla ciągu bitów: każdy test z `tests` przy progu `alpha`. Zwraca
+{"n_bits", "alpha", "passed" (wszystkie testy), "tests": [...], "time"}, gdzie
+time to łączny czas testów w sekundach.
+*/
+pub fn report<B: BitSequence + ?Sized>(
+    bits: &B,
+    tests: &[NistTest],
+    alpha: f64,
+) -> Map<String, Value> {
+    let start = Instant::now();
+    let entries: Vec<Value> = tests
+        .iter()
+        .map(|test| test.run(bits).report_entry(*test, alpha))
+        .collect();
+    let passed = entries
+        .iter()
+        .all(|entry| entry["passed"].as_bool() == Some(true));
+
+    let mut report = Map::new();
+    report.insert("n_bits".to_string(), json!(bits.len()));
+    report.insert("alpha".to_string(), json!(alpha));
+    report.insert("passed".to_string(), json!(passed));
+    report.insert("tests".to_string(), json!(entries));
+    report.insert("time".to_string(), json!(start.elapsed().as_secs_f64()));
+    report
+}
+
+/*
+round(x, digits) z Pythona: poprawnie zaokrąglony zapis dziesiętny wartości
+binarnej, remisy do parzystej — formatowanie Rusta z precyzją robi to samo.
+*/
+pub fn round_python(x: f64, digits: usize) -> f64 {
+    format!("{:.*}", digits, x).parse().unwrap_or(x)
+}
+
+// Statystyki budowane w kolejności kluczy z Pythona
+fn statistics<const N: usize>(entries: [(&str, Value); N]) -> Map<String, Value> {
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
+
+// Dla pustego ciągu Python kończy się ZeroDivisionError — tu to zwykły błąd testu
+fn empty_error() -> TestResult {
+    TestResult::error("No bits to test", Map::new())
+}
+
+// Wzorzec m bitów zaczynający się na pozycji i (cyklicznie), MSB-first
+fn cyclic_patterns<B: BitSequence + ?Sized>(
+    bits: &B,
+    m: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    let n = bits.len();
+    let mask = (1usize << m) - 1;
+    let mut window = (0..m).fold(0usize, |acc, j| (acc << 1) | bits.bit(j % n) as usize);
+    (0..n).map(move |i| {
+        let pattern = window;
+        window = ((window << 1) | bits.bit((i + m) % n) as usize) & mask;
+        pattern
+    })
+}
+
+fn count_ones<B: BitSequence + ?Sized>(bits: &B) -> usize {
+    bit_chunks(bits, SCAN_CHUNK)
+        .map(|chunk| chunk.iter().filter(|&&bit| bit == 1).count())
+        .sum()
+}
+
+fn chi_square_p_value(chi_square: f64) -> f64 {
+    erfc((chi_square / 2.0).sqrt())
+}
+
+pub fn monobit<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let n = bits.len();
+    if n == 0 {
+        return empty_error();
+    }
+    let ones = count_ones(bits);
+    let s = 2 * ones as i64 - n as i64;
+
+    let s_obs = s.abs() as f64 / (n as f64).sqrt();
+    let p_value = erfc(s_obs / 2f64.sqrt());
+
+    TestResult::from_p_value(
+        p_value,
+        s_obs,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("test_statistic", json!(round_python(s_obs, 6))),
+            ("ones", json!(ones)),
+            ("zeros", json!(n - ones)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn block_frequency<B: BitSequence + ?Sized>(bits: &B, block_size: usize) -> TestResult {
+    let num_blocks = bits.len().checked_div(block_size).unwrap_or(0);
+    if num_blocks == 0 {
+        return TestResult::error("Not enough bits for block test", Map::new());
+    }
+
+    let mut chi_square = 0.0;
+    for block in bit_chunks(bits, block_size).take(num_blocks) {
+        let ones = block.iter().filter(|&&bit| bit == 1).count();
+        let proportion = ones as f64 / block_size as f64;
+        chi_square += (proportion - 0.5).powi(2);
+    }
+    chi_square *= (4 * block_size) as f64;
+
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            ("num_blocks", json!(num_blocks)),
+            ("block_size", json!(block_size)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn runs<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let n = bits.len();
+    if n == 0 {
+        return empty_error();
+    }
+    let ones = count_ones(bits);
+    let pi = ones as f64 / n as f64;
+
+    // Pre-test: proporcja jedynek musi być bliska 0.5
+    if (pi - 0.5).abs() >= 2.0 / (n as f64).sqrt() {
+        return TestResult::error(
+            "Pre-test failed: proportion of ones not close to 0.5",
+            statistics([("proportion", json!(round_python(pi, 6)))]),
+        );
+    }
+
+    // Zmiany bitu liczone także na granicy fragmentów
+    let mut runs = 1;
+    let mut previous = bits.bit(0);
+    for chunk in bit_chunks(bits, SCAN_CHUNK) {
+        for &bit in chunk.iter() {
+            if bit != previous {
+                runs += 1;
+                previous = bit;
+            }
+        }
+    }
+    let expected_runs = (2 * n) as f64 * pi * (1.0 - pi);
+
+    let numerator = (runs as f64 - expected_runs).abs();
+    let denominator = 2.0 * (2.0 * n as f64).sqrt() * pi * (1.0 - pi);
+    let test_stat = if denominator != 0.0 {
+        numerator / denominator
+    } else {
+        0.0
+    };
+    let p_value = erfc(test_stat / 2f64.sqrt());
+
+    TestResult::from_p_value(
+        p_value,
+        test_stat,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("runs", json!(runs)),
+            ("expected_runs", json!(round_python(expected_runs, 2))),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn longest_run<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let n = bits.len();
+
+    // Parametry (K, M, v, pi) jak w backendzie
+    let (k, m, v_values, pi_values): (usize, usize, &[usize], &[f64]) = if n < 128 {
+        return TestResult::error("Minimum 128 bits required", Map::new());
+    } else if n < 6272 {
+        (3, 8, &[1, 2, 3, 4], &[0.2148, 0.3672, 0.2305, 0.1875])
+    } else if n < 750000 {
+        (
+            5,
+            128,
+            &[4, 5, 6, 7, 8, 9],
+            &[0.1174, 0.2430, 0.2493, 0.1752, 0.1027, 0.1124],
+        )
+    } else {
+        (
+            6,
+            10000,
+            &[10, 11, 12, 13, 14, 15, 16],
+            &[0.0882, 0.2092, 0.2483, 0.1933, 0.1208, 0.0675, 0.0727],
+        )
+    };
+
+    let num_blocks = n / m;
+    let mut frequencies = vec![0usize; k + 1];
+    for block in bit_chunks(bits, m).take(num_blocks) {
+        let mut max_run = 0;
+        let mut current_run = 0;
+        for &bit in block.iter() {
+            if bit == 1 {
+                current_run += 1;
+                max_run = max_run.max(current_run);
+            } else {
+                current_run = 0;
+            }
+        }
+
+        // Kategoria: <= v[0], (v[j], v[j+1]] albo >= v[K]
+        let category = if max_run <= v_values[0] {
+            0
+        } else if max_run >= v_values[v_values.len() - 1] {
+            k
+        } else {
+            (0..v_values.len() - 1)
+                .find(|&j| v_values[j] < max_run && max_run <= v_values[j + 1])
+                .map_or(k, |j| j + 1)
+        };
+        frequencies[category] += 1;
+    }
+
+    let chi_square = (0..=k).fold(0.0, |acc, i| {
+        let expected = num_blocks as f64 * pi_values[i];
+        acc + (frequencies[i] as f64 - expected).powi(2) / expected
+    });
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            ("frequencies", json!(frequencies)),
+            ("num_blocks", json!(num_blocks)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn cumulative_sums<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let n = bits.len();
+    if n == 0 {
+        return empty_error();
+    }
+
+    // Największe |S_k| (S_0 = 0) sumy kroczącej ±1
+    let mut sum = 0i64;
+    let mut z = 0i64;
+    for chunk in bit_chunks(bits, SCAN_CHUNK) {
+        for &bit in chunk.iter() {
+            sum += if bit == 1 { 1 } else { -1 };
+            z = z.max(sum.abs());
+        }
+    }
+
+    let n_f = n as f64;
+    let sqrt_n = n_f.sqrt();
+    // int() w Pythonie obcina w stronę zera
+    let start = ((-n_f / z as f64 + 1.0) / 4.0).trunc() as i64;
+    let end = ((n_f / z as f64 - 1.0) / 4.0).trunc() as i64;
+    let mut sum_val = 0.0;
+    for k in start..=end {
+        let term1 = erfc(((4 * k + 1) * z) as f64 / sqrt_n / 2f64.sqrt());
+        let term2 = erfc(((4 * k - 1) * z) as f64 / sqrt_n / 2f64.sqrt());
+        sum_val += term1 - term2;
+    }
+    let p_value = 1.0 - sum_val;
+
+    TestResult {
+        passed: p_value >= THRESHOLD,
+        score: round_python(p_value.clamp(0.0, 1.0), 4),
+        p_values: vec![p_value],
+        test_statistics: vec![z as f64],
+        statistics: statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("max_excursion", json!(z)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    }
+}
+
+// floor(log2(n)) jak int(math.log2(n))
+fn floor_log2(n: usize) -> i64 {
+    (n as f64).log2() as i64
+}
+
+pub fn approximate_entropy<B: BitSequence + ?Sized>(bits: &B, m: usize) -> TestResult {
+    let n = bits.len();
+    if n < 100 {
+        return TestResult::error("Minimum 100 bits required", Map::new());
+    }
+    let m = (m as i64).min(floor_log2(n) - 5).max(2) as usize;
+
+    // Suma po wzorcach w kolejności pierwszego wystąpienia, jak po słowniku
+    // w Pythonie — ta sama kolejność dodawania daje te same bity wyniku
+    let phi = |m_local: usize| -> f64 {
+        let mut counts = vec![0usize; 1 << m_local];
+        let mut order = Vec::new();
+        for pattern in cyclic_patterns(bits, m_local) {
+            if counts[pattern] == 0 {
+                order.push(pattern);
+            }
+            counts[pattern] += 1;
+        }
+        order.iter().fold(0.0, |acc, &pattern| {
+            let frequency = counts[pattern] as f64 / n as f64;
+            acc + frequency * frequency.ln()
+        })
+    };
+
+    let apen = phi(m) - phi(m + 1);
+    let chi_square = (2 * n) as f64 * (LN_2 - apen);
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("approximate_entropy", json!(round_python(apen, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            ("m", json!(m)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+// Ranga macierzy nad GF(2), wiersze jako maski bitowe
+fn binary_rank(mut rows: Vec<u32>, columns: usize) -> usize {
+    let mut rank = 0;
+    for column in 0..columns.min(rows.len()) {
+        let bit = 1u32 << column;
+        let Some(pivot) = (rank..rows.len()).find(|&row| rows[row] & bit != 0) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let pivot_row = rows[rank];
+        for (index, row) in rows.iter_mut().enumerate() {
+            if index != rank && *row & bit != 0 {
+                *row ^= pivot_row;
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+pub fn matrix_rank<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    const M: usize = 32;
+    const Q: usize = 32;
+
+    let n = bits.len();
+    if n < M * Q {
+        return TestResult::error(&format!("Minimum {} bits required", M * Q), Map::new());
+    }
+
+    let num_matrices = n / (M * Q);
+    let (mut full, mut full_minus_one, mut other) = (0usize, 0usize, 0usize);
+    for block in bit_chunks(bits, M * Q).take(num_matrices) {
+        let rows: Vec<u32> = block
+            .chunks_exact(Q)
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .fold(0u32, |acc, (column, &bit)| acc | ((bit as u32) << column))
+            })
+            .collect();
+        match binary_rank(rows, Q) {
+            rank if rank == M => full += 1,
+            rank if rank == M - 1 => full_minus_one += 1,
+            _ => other += 1,
+        }
+    }
+
+    // Prawdopodobieństwa teoretyczne dla M = Q = 32
+    let chi_square = [(full, 0.2888), (full_minus_one, 0.5776), (other, 0.1336)]
+        .iter()
+        .fold(0.0, |acc, &(count, pi)| {
+            let expected = num_matrices as f64 * pi;
+            acc + (count as f64 - expected).powi(2) / expected
+        });
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            (
+                "rank_counts",
+                json!({
+                    M.to_string(): full,
+                    (M - 1).to_string(): full_minus_one,
+                    "other": other,
+                }),
+            ),
+            ("num_matrices", json!(num_matrices)),
+            ("matrix_size", json!(format!("{}x{}", M, Q))),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn dft<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let n = bits.len();
+    if n < 100 {
+        return TestResult::error("Minimum 100 bits required", Map::new());
+    }
+    if n > DFT_MAX_BITS {
+        return TestResult::error("Maximum 2^30 bits supported", Map::new());
+    }
+
+    // |S_k| dla k < n/2, S_k = sum x_j e^{-2 pi i k j / n}, x_j = ±1 — przez FFT
+    let threshold = ((1.0 / 0.05f64).ln() * n as f64).sqrt();
+    let mut below = 0usize;
+    real_spectrum_magnitudes(
+        n,
+        DFT_MEMORY,
+        |j| if bits.bit(j) == 1 { 1.0 } else { -1.0 },
+        |_, magnitude| {
+            if magnitude < threshold {
+                below += 1;
+            }
+        },
+    );
+
+    let n0 = 0.95 * n as f64 / 2.0;
+    let d = (below as f64 - n0) / (n as f64 * 0.95 * 0.05 / 4.0).sqrt();
+    let p_value = erfc(d.abs() / 2f64.sqrt());
+
+    TestResult::from_p_value(
+        p_value,
+        d,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("peaks_below_threshold", json!(below)),
+            ("expected_peaks", json!(round_python(n0, 2))),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn non_overlapping_template<B: BitSequence + ?Sized>(bits: &B, template: &[u8]) -> TestResult {
+    const M: usize = 1000;
+
+    let n = bits.len();
+    let m = template.len();
+    if n < M {
+        return TestResult::error(&format!("Minimum {} bits required", M), Map::new());
+    }
+
+    let num_blocks = n / M;
+    let counts: Vec<usize> = bit_chunks(bits, M)
+        .take(num_blocks)
+        .map(|block| {
+            let mut count = 0;
+            let mut i = 0;
+            while i + m <= block.len() {
+                if &block[i..i + m] == template {
+                    count += 1;
+                    // Przeskocz wzorzec (bez nakładania)
+                    i += m;
+                } else {
+                    i += 1;
+                }
+            }
+            count
+        })
+        .collect();
+
+    let mu = (M - m + 1) as f64 / 2f64.powi(m as i32);
+    let sigma_sq =
+        M as f64 * ((1.0 / 2f64.powi(m as i32)) - ((2 * m - 1) as f64 / 2f64.powi(2 * m as i32)));
+    let chi_square = counts
+        .iter()
+        .fold(0.0, |acc, &count| acc + (count as f64 - mu).powi(2))
+        / sigma_sq;
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            ("template", json!(template)),
+            ("num_blocks", json!(num_blocks)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn overlapping_template<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    const M: usize = 1032;
+    // Wzorzec 111111111
+    const TEMPLATE_LEN: usize = 9;
+    const PI_VALUES: [f64; 6] = [0.364091, 0.185659, 0.139381, 0.100571, 0.0704323, 0.139865];
+
+    let n = bits.len();
+    if n < M {
+        return TestResult::error(&format!("Minimum {} bits required", M), Map::new());
+    }
+
+    let num_blocks = n / M;
+    let mut frequencies = [0usize; 6];
+    for block in bit_chunks(bits, M).take(num_blocks) {
+        let count = block
+            .windows(TEMPLATE_LEN)
+            .filter(|window| window.iter().all(|&bit| bit == 1))
+            .count();
+        frequencies[count.min(5)] += 1;
+    }
+
+    let chi_square = (0..6).fold(0.0, |acc, i| {
+        let expected = num_blocks as f64 * PI_VALUES[i];
+        acc + (frequencies[i] as f64 - expected).powi(2) / expected
+    });
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            ("frequencies", json!(frequencies)),
+            ("num_blocks", json!(num_blocks)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn universal<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let n = bits.len();
+
+    // (L, Q, wartość oczekiwana, wariancja) z tabeli NIST
+    let (l, q, expected, c) = if n < 387840 {
+        (6usize, 640usize, 5.2177052, 2.576)
+    } else if n < 904960 {
+        (7, 1280, 6.1962507, 3.125)
+    } else {
+        (8, 2560, 7.1836656, 3.238)
+    };
+
+    let k = (n / l) as i64 - q as i64;
+    if k <= 0 {
+        return TestResult::error(
+            &format!("Minimum {} bits required", (q + 100) * l),
+            Map::new(),
+        );
+    }
+    let k = k as usize;
+
+    // Ostatnie wystąpienie każdego L-bitowego bloku (0 = jeszcze nie było)
+    let block_value = |i: usize| -> usize {
+        ((i - 1) * l..i * l).fold(0, |acc, j| (acc << 1) | bits.bit(j) as usize)
+    };
+    let mut last_seen = vec![0usize; 1 << l];
+    for i in 1..=q {
+        last_seen[block_value(i)] = i;
+    }
+    let mut sum_log = 0.0;
+    for i in q + 1..=q + k {
+        let block = block_value(i);
+        if last_seen[block] != 0 {
+            sum_log += ((i - last_seen[block]) as f64).log2();
+        }
+        last_seen[block] = i;
+    }
+
+    let f_n = sum_log / k as f64;
+    let test_stat = (f_n - expected).abs() / (c / (k as f64).sqrt());
+    let p_value = erfc(test_stat / 2f64.sqrt());
+
+    TestResult::from_p_value(
+        p_value,
+        test_stat,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("fn", json!(round_python(f_n, 6))),
+            ("expected", json!(round_python(expected, 6))),
+            ("L", json!(l)),
+            ("Q", json!(q)),
+            ("K", json!(k)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn linear_complexity<B: BitSequence + ?Sized>(bits: &B, m: usize) -> TestResult {
+    const PI_VALUES: [f64; 7] = [0.010417, 0.03125, 0.125, 0.5, 0.25, 0.0625, 0.020833];
+    const T: [f64; 6] = [-2.5, -1.5, -0.5, 0.5, 1.5, 2.5];
+
+    let num_blocks = bits.len().checked_div(m).unwrap_or(0);
+    if num_blocks < 200 {
+        return TestResult::error(
+            "Need at least 200 blocks (minimum 100000 bits for M=500)",
+            Map::new(),
+        );
+    }
+
+    let m_f = m as f64;
+    let sign = if (m + 1).is_multiple_of(2) { 1.0 } else { -1.0 };
+    let mu = m_f / 2.0 + (9.0 + sign) / 36.0 - (m_f / 3.0 + 2.0 / 9.0) / 2f64.powi(m as i32);
+
+    let mut frequencies = [0usize; 7];
+    for block in bit_chunks(bits, m).take(num_blocks) {
+        let complexity = berlekamp_massey(&*block) as f64;
+        // (M / 2.0) ** 0.5 w Pythonie to pow, nie sqrt
+        let t_i = (complexity - mu + 2.0 / 9.0) / (m_f / 2.0).powf(0.5);
+        let category = if t_i <= T[0] {
+            0
+        } else if t_i > T[5] {
+            6
+        } else {
+            (0..5)
+                .find(|&j| T[j] < t_i && t_i <= T[j + 1])
+                .map_or(6, |j| j + 1)
+        };
+        frequencies[category] += 1;
+    }
+
+    let chi_square = (0..7).fold(0.0, |acc, i| {
+        let expected = num_blocks as f64 * PI_VALUES[i];
+        acc + (frequencies[i] as f64 - expected).powi(2) / expected
+    });
+    let p_value = chi_square_p_value(chi_square);
+
+    TestResult::from_p_value(
+        p_value,
+        chi_square,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("chi_square", json!(round_python(chi_square, 6))),
+            ("frequencies", json!(frequencies)),
+            ("M", json!(m)),
+            ("N", json!(num_blocks)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn serial<B: BitSequence + ?Sized>(bits: &B, m: usize) -> TestResult {
+    let n = bits.len();
+    if n < 100 {
+        return TestResult::error("Minimum 100 bits required", Map::new());
+    }
+    let m = (m as i64).min(floor_log2(n) - 2).max(2) as usize;
+
+    // psi^2_m = 2^m / n * sum(liczność^2) - n, wzorce cykliczne
+    let psi_sq = |m_local: usize| -> f64 {
+        let mut counts = vec![0u64; 1 << m_local];
+        for pattern in cyclic_patterns(bits, m_local) {
+            counts[pattern] += 1;
+        }
+        let sum_val: u64 = counts.iter().map(|count| count * count).sum();
+        2f64.powi(m_local as i32) / n as f64 * sum_val as f64 - n as f64
+    };
+
+    let psi2_m = psi_sq(m);
+    let psi2_m1 = psi_sq(m - 1);
+    let psi2_m2 = psi_sq(m - 2);
+    let delta1 = psi2_m - psi2_m1;
+    let delta2 = psi2_m - 2.0 * psi2_m1 + psi2_m2;
+
+    let p_value1 = chi_square_p_value(delta1.abs());
+    let p_value2 = chi_square_p_value(delta2.abs());
+
+    TestResult {
+        passed: p_value1 >= THRESHOLD && p_value2 >= THRESHOLD,
+        score: round_python(p_value1.min(p_value2).min(1.0), 4),
+        p_values: vec![p_value1, p_value2],
+        test_statistics: vec![delta1, delta2],
+        statistics: statistics([
+            ("p_value1", json!(round_python(p_value1, 6))),
+            ("p_value2", json!(round_python(p_value2, 6))),
+            ("delta1", json!(round_python(delta1, 6))),
+            ("delta2", json!(round_python(delta2, 6))),
+            ("m", json!(m)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    }
+}
+
+// Liczba powrotów do zera i odwiedzin stanów -9..=9 błądzenia losowego ±1
+fn excursion_visits<B: BitSequence + ?Sized>(bits: &B) -> (usize, [usize; 19]) {
+    let mut visits = [0usize; 19];
+    let mut cycles = 0;
+    let mut position = 0i64;
+    visits[9] = 1;
+    for chunk in bit_chunks(bits, SCAN_CHUNK) {
+        for &bit in chunk.iter() {
+            position += if bit == 1 { 1 } else { -1 };
+            if position == 0 {
+                cycles += 1;
+            }
+            if position.abs() <= 9 {
+                visits[(position + 9) as usize] += 1;
+            }
+        }
+    }
+    (cycles, visits)
+}
+
+fn too_few_cycles(cycles: usize) -> TestResult {
+    TestResult::error(
+        "Too few cycles (need >= 500)",
+        statistics([("cycles", json!(cycles))]),
+    )
+}
+
+// Uproszczone prawdopodobieństwa z _excursion_probability
+fn excursion_probability(state: i64) -> f64 {
+    match state.abs() {
+        1 => 0.1458,
+        2 => 0.0537,
+        3 => 0.0163,
+        4 => 0.0046,
+        _ => 0.0,
+    }
+}
+
+pub fn random_excursions<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let (cycles, visits) = excursion_visits(bits);
+    if cycles < 500 {
+        return too_few_cycles(cycles);
+    }
+
+    let mut states = Vec::new();
+    let mut chi_sum = 0.0;
+    for state in [-4i64, -3, -2, -1, 1, 2, 3, 4] {
+        let count = visits[(state + 9) as usize];
+        let expected = cycles as f64 * excursion_probability(state);
+        let chi = if expected > 0.0 {
+            (count as f64 - expected).powi(2) / expected
+        } else {
+            0.0
+        };
+        // Średnia liczona jest z zaokrąglonych wartości, jak w Pythonie
+        let chi = round_python(chi, 4);
+        chi_sum += chi;
+        states.push(json!({
+            "state": state,
+            "visits": count,
+            "expected": round_python(expected, 2),
+            "chi_square": chi,
+        }));
+    }
+
+    let avg_chi = chi_sum / states.len() as f64;
+    let p_value = chi_square_p_value(avg_chi);
+
+    TestResult::from_p_value(
+        p_value,
+        avg_chi,
+        statistics([
+            ("p_value", json!(round_python(p_value, 6))),
+            ("cycles", json!(cycles)),
+            ("avg_chi_square", json!(round_python(avg_chi, 4))),
+            ("states", json!(states)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    )
+}
+
+pub fn random_excursions_variant<B: BitSequence + ?Sized>(bits: &B) -> TestResult {
+    let (cycles, visits) = excursion_visits(bits);
+    if cycles < 500 {
+        return too_few_cycles(cycles);
+    }
+
+    let mut states = Vec::new();
+    let mut p_values = Vec::new();
+    let mut test_statistics = Vec::new();
+    for state in (-9i64..=9).filter(|&state| state != 0) {
+        let count = visits[(state + 9) as usize];
+        let stat = (count as f64 - cycles as f64).abs()
+            / ((2 * cycles) as f64 * (4 * state.abs() - 2) as f64).sqrt();
+        let p_value = erfc(stat / 2f64.sqrt());
+        p_values.push(p_value);
+        test_statistics.push(stat);
+        states.push(json!({
+            "state": state,
+            "visits": count,
+            "p_value": round_python(p_value, 6),
+        }));
+    }
+
+    let min_p_value = p_values.iter().copied().fold(f64::INFINITY, f64::min);
+    // Jak w Pythonie: tylko pierwsze 6 stanów w statystykach
+    states.truncate(6);
+
+    TestResult {
+        passed: p_values.iter().all(|&p| p >= THRESHOLD),
+        score: round_python(min_p_value.min(1.0), 4),
+        p_values,
+        test_statistics,
+        statistics: statistics([
+            ("min_p_value", json!(round_python(min_p_value, 6))),
+            ("cycles", json!(cycles)),
+            ("states", json!(states)),
+            ("threshold", json!(THRESHOLD)),
+        ]),
+    }
+}
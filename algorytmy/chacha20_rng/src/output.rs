@@ -0,0 +1,538 @@
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::stream::BitSink;
+
+/*
+Serializacja wyniku generatora.
+
+    to_json(bits, elapsed, metadata) -> {"bits": [1,0,...], "time": 0.001234, ...metadata}
+    to_hex(bytes)                    -> "00ff..." (małe litery)
+    to_json_base64(bits, msb_first, elapsed, metadata)
+                                     -> {"bits_base64": "...", "n_bits": N, "bit_order": "msb", ...}
+    pack_bits(bits, msb_first)       -> bity spakowane po 8 w bajt (format raw)
+    to_base64(bytes)                 -> base64 (RFC 4648, z dopełnieniem '=')
+    to_ascii01(bits)                 -> tekst ze znaków '0'/'1' (format NIST STS assess)
+    wrap_lines(text, width)          -> tekst łamany co `width` znaków (0 = bez łamania)
+
+`metadata` to dodatkowe pola raportu (np. seed, klucz i nonce ChaCha20),
+dopisywane obok "bits" i "time".
+
+Strumieniowe odpowiedniki powyższych funkcji (stała pamięć, zapis na bieżąco):
+
+    create_sink(format, jsonBits, width, msbFirst, writer) -> Box<dyn BitSink>
+
+Wynik jest bajt w bajt taki sam jak z wersji w pamięci, z tą różnicą, że
+w JSON pole "bits"/"bits_base64" jest zapisywane jako pierwsze.
+*/
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+// Koduje 1..=3 bajty jako 4 znaki base64 (z dopełnieniem '=')
+fn base64_group(chunk: &[u8]) -> [u8; 4] {
+    let b0 = chunk[0] as u32;
+    let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+    let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+    let triple = (b0 << 16) | (b1 << 8) | b2;
+
+    let mut group = [b'='; 4];
+    for (i, slot) in group.iter_mut().enumerate().take(chunk.len() + 1) {
+        *slot = BASE64_ALPHABET[((triple >> (18 - 6 * i)) & 0x3F) as usize];
+    }
+    group
+}
+
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        out.extend(base64_group(chunk).iter().map(|&c| c as char));
+    }
+    out
+}
+
+pub fn to_ascii01(bits: &[u8]) -> String {
+    bits.iter()
+        .map(|&bit| if bit & 1 == 1 { '1' } else { '0' })
+        .collect()
+}
+
+pub fn wrap_lines(text: &str, width: usize) -> String {
+    if width == 0 {
+        let mut out = text.to_string();
+        out.push('\n');
+        return out;
+    }
+
+    // Wszystkie formaty tekstowe są ASCII, więc można dzielić po bajtach
+    let mut out = String::with_capacity(text.len() + text.len() / width + 1);
+    for line in text.as_bytes().chunks(width) {
+        out.push_str(std::str::from_utf8(line).unwrap_or(""));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn to_json(bits: &[u8], elapsed: f64, metadata: Map<String, Value>) -> String {
+    let mut result = Map::new();
+
+    // Konwertuj na liczby (0 i 1)
+    result.insert("bits".to_string(), json!(bits));
+    result.insert("time".to_string(), json!(elapsed));
+    result.extend(metadata);
+
+    Value::Object(result).to_string()
+}
+
+// Wariant JSON z bitami spakowanymi jak w --format raw i zakodowanymi base64
+pub fn to_json_base64(
+    bits: &[u8],
+    msb_first: bool,
+    elapsed: f64,
+    metadata: Map<String, Value>,
+) -> String {
+    let mut result = Map::new();
+
+    result.insert(
+        "bits_base64".to_string(),
+        json!(to_base64(&pack_bits(bits, msb_first))),
+    );
+    result.insert("n_bits".to_string(), json!(bits.len()));
+    result.insert(
+        "bit_order".to_string(),
+        json!(if msb_first { "msb" } else { "lsb" }),
+    );
+    result.insert("time".to_string(), json!(elapsed));
+    result.extend(metadata);
+
+    Value::Object(result).to_string()
+}
+
+// Format wyjścia wybierany opcją --format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Raw,
+    Hex,
+    Base64,
+    Ascii01,
+}
+
+impl Format {
+    // Domyślna szerokość linii dla formatów tekstowych (0 = bez łamania)
+    pub fn default_width(&self) -> usize {
+        match self {
+            Format::Json | Format::Raw => 0,
+            Format::Hex => 64,
+            Format::Base64 => 76,
+            Format::Ascii01 => 80,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "raw" | "bin" => Ok(Format::Raw),
+            "hex" => Ok(Format::Hex),
+            "base64" => Ok(Format::Base64),
+            "ascii01" => Ok(Format::Ascii01),
+            _ => Err(format!(
+                "nieznany format wyjścia: '{}' (dostępne: json, raw, hex, base64, ascii01)",
+                text
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Format::Json => "json",
+            Format::Raw => "raw",
+            Format::Hex => "hex",
+            Format::Base64 => "base64",
+            Format::Ascii01 => "ascii01",
+        };
+        f.write_str(name)
+    }
+}
+
+// Kodowanie bitów w formacie JSON wybierane opcją --json-bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonBits {
+    // "bits": [1,0,1,...] — dotychczasowy kontrakt
+    Array,
+    // "bits_base64": "..." — spakowane bajty, znacznie krótszy zapis
+    Base64,
+}
+
+impl FromStr for JsonBits {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_lowercase().as_str() {
+            "array" => Ok(JsonBits::Array),
+            "base64" => Ok(JsonBits::Base64),
+            _ => Err(format!(
+                "nieznane kodowanie bitów w JSON: '{}' (dostępne: array, base64)",
+                text
+            )),
+        }
+    }
+}
+
+/*
+Pakowanie bitów do bajtów z zachowaniem semantyki msb_first:
+    msb_first = true  -> pierwszy bit strumienia trafia na najstarszy bit bajtu
+    msb_first = false -> pierwszy bit strumienia trafia na najmłodszy bit bajtu
+Niepełny ostatni bajt jest dopełniany zerami.
+*/
+pub fn pack_bits(bits: &[u8], msb_first: bool) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            let mut byte = 0u8;
+            for (i, &bit) in chunk.iter().enumerate() {
+                if bit & 1 == 1 {
+                    byte |= if msb_first { 0x80 >> i } else { 1 << i };
+                }
+            }
+            byte
+        })
+        .collect()
+}
+
+// Zapis tekstu ASCII z łamaniem linii co `width` znaków (0 = bez łamania)
+struct LineWriter<W: Write> {
+    writer: W,
+    width: usize,
+    column: usize,
+}
+
+impl<W: Write> LineWriter<W> {
+    fn new(writer: W, width: usize) -> Self {
+        LineWriter {
+            writer,
+            width,
+            column: 0,
+        }
+    }
+
+    fn write_text(&mut self, mut text: &[u8]) -> io::Result<()> {
+        if self.width == 0 {
+            return self.writer.write_all(text);
+        }
+
+        while !text.is_empty() {
+            if self.column == self.width {
+                self.writer.write_all(b"\n")?;
+                self.column = 0;
+            }
+            let take = (self.width - self.column).min(text.len());
+            self.writer.write_all(&text[..take])?;
+            self.column += take;
+            text = &text[take..];
+        }
+        Ok(())
+    }
+
+    fn finish_line(&mut self) -> io::Result<()> {
+        self.writer.write_all(b"\n")?;
+        self.column = 0;
+        self.writer.flush()
+    }
+}
+
+// Dopisuje `,"klucz":wartość` dla każdego pola metadanych
+fn write_json_fields<W: Write>(writer: &mut W, fields: &Map<String, Value>) -> io::Result<()> {
+    for (name, value) in fields {
+        write!(writer, ",{}:{}", Value::String(name.clone()), value)?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Raw,
+    Hex,
+    Base64,
+}
+
+// Odbiorca pakujący bity w bajty: formaty raw, hex, base64 i JSON z bits_base64
+struct PackedSink<W: Write> {
+    out: LineWriter<W>,
+    encoding: Encoding,
+    msb_first: bool,
+    json: bool,
+    started: bool,
+    current: u8,
+    filled: u32,
+    pending: Vec<u8>,
+    bytes: Vec<u8>,
+    text: Vec<u8>,
+    n_bits: u64,
+}
+
+impl<W: Write> PackedSink<W> {
+    fn new(writer: W, encoding: Encoding, width: usize, msb_first: bool, json: bool) -> Self {
+        PackedSink {
+            out: LineWriter::new(writer, if json { 0 } else { width }),
+            encoding,
+            msb_first,
+            json,
+            started: false,
+            current: 0,
+            filled: 0,
+            pending: Vec::new(),
+            bytes: Vec::new(),
+            text: Vec::new(),
+            n_bits: 0,
+        }
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            if self.json {
+                self.out.writer.write_all(b"{\"bits_base64\":\"")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn push_bit(&mut self, bit: u8) {
+        if bit & 1 == 1 {
+            self.current |= if self.msb_first {
+                0x80 >> self.filled
+            } else {
+                1 << self.filled
+            };
+        }
+        self.filled += 1;
+        if self.filled == 8 {
+            self.bytes.push(self.current);
+            self.current = 0;
+            self.filled = 0;
+        }
+    }
+
+    // Koduje spakowane bajty z self.bytes i zapisuje je na wyjście
+    fn emit_bytes(&mut self) -> io::Result<()> {
+        match self.encoding {
+            Encoding::Raw => self.out.writer.write_all(&self.bytes)?,
+            Encoding::Hex => {
+                self.text.clear();
+                for &byte in &self.bytes {
+                    self.text.push(HEX_DIGITS[(byte >> 4) as usize]);
+                    self.text.push(HEX_DIGITS[(byte & 0x0F) as usize]);
+                }
+                self.out.write_text(&self.text)?;
+            }
+            Encoding::Base64 => {
+                // Base64 koduje grupy po 3 bajty; reszta czeka na kolejną paczkę
+                self.pending.extend_from_slice(&self.bytes);
+                let complete = self.pending.len() / 3 * 3;
+                self.text.clear();
+                for group in self.pending[..complete].chunks_exact(3) {
+                    self.text.extend_from_slice(&base64_group(group));
+                }
+                self.pending.drain(..complete);
+                self.out.write_text(&self.text)?;
+            }
+        }
+        self.bytes.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> BitSink for PackedSink<W> {
+    fn write_bits(&mut self, bits: &[u8]) -> io::Result<()> {
+        self.start()?;
+
+        // Najpierw dokończ bajt zaczęty w poprzedniej paczce
+        let mut rest = bits;
+        while self.filled > 0 && !rest.is_empty() {
+            self.push_bit(rest[0]);
+            rest = &rest[1..];
+        }
+
+        // Pełne bajty pakowane po 8 bitów naraz
+        let chunks = rest.chunks_exact(8);
+        let tail = chunks.remainder();
+        for chunk in chunks {
+            let byte = if self.msb_first {
+                chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | (bit & 1))
+            } else {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0u8, |acc, &bit| (acc << 1) | (bit & 1))
+            };
+            self.bytes.push(byte);
+        }
+        for &bit in tail {
+            self.push_bit(bit);
+        }
+
+        self.n_bits += bits.len() as u64;
+        self.emit_bytes()
+    }
+
+    fn finish(&mut self, elapsed: f64, metadata: Map<String, Value>) -> io::Result<()> {
+        self.start()?;
+
+        // Niepełny ostatni bajt jest dopełniany zerami, jak w pack_bits
+        if self.filled > 0 {
+            self.bytes.push(self.current);
+            self.filled = 0;
+        }
+        self.emit_bytes()?;
+        if !self.pending.is_empty() {
+            let group = base64_group(&self.pending);
+            self.pending.clear();
+            self.out.write_text(&group)?;
+        }
+
+        if self.json {
+            let mut fields = Map::new();
+            fields.insert("n_bits".to_string(), json!(self.n_bits));
+            fields.insert(
+                "bit_order".to_string(),
+                json!(if self.msb_first { "msb" } else { "lsb" }),
+            );
+            fields.insert("time".to_string(), json!(elapsed));
+            fields.extend(metadata);
+
+            self.out.writer.write_all(b"\"")?;
+            write_json_fields(&mut self.out.writer, &fields)?;
+            self.out.writer.write_all(b"}")?;
+            return self.out.finish_line();
+        }
+
+        match self.encoding {
+            Encoding::Raw => self.out.writer.flush(),
+            Encoding::Hex | Encoding::Base64 => self.out.finish_line(),
+        }
+    }
+}
+
+// Odbiorca formatu ascii01: znak '0'/'1' na bit
+struct Ascii01Sink<W: Write> {
+    out: LineWriter<W>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> BitSink for Ascii01Sink<W> {
+    fn write_bits(&mut self, bits: &[u8]) -> io::Result<()> {
+        self.buffer.clear();
+        self.buffer.extend(
+            bits.iter()
+                .map(|&bit| if bit & 1 == 1 { b'1' } else { b'0' }),
+        );
+        self.out.write_text(&self.buffer)
+    }
+
+    fn finish(&mut self, _elapsed: f64, _metadata: Map<String, Value>) -> io::Result<()> {
+        self.out.finish_line()
+    }
+}
+
+// Odbiorca formatu JSON z tablicą "bits": [1,0,...]
+struct JsonArraySink<W: Write> {
+    writer: W,
+    started: bool,
+    first: bool,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> JsonArraySink<W> {
+    fn start(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            self.writer.write_all(b"{\"bits\":[")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> BitSink for JsonArraySink<W> {
+    fn write_bits(&mut self, bits: &[u8]) -> io::Result<()> {
+        self.start()?;
+        self.buffer.clear();
+        for &bit in bits {
+            if !self.first {
+                self.buffer.push(b',');
+            }
+            self.first = false;
+            self.buffer.push(if bit & 1 == 1 { b'1' } else { b'0' });
+        }
+        self.writer.write_all(&self.buffer)
+    }
+
+    fn finish(&mut self, elapsed: f64, metadata: Map<String, Value>) -> io::Result<()> {
+        self.start()?;
+
+        let mut fields = Map::new();
+        fields.insert("time".to_string(), json!(elapsed));
+        fields.extend(metadata);
+
+        self.writer.write_all(b"]")?;
+        write_json_fields(&mut self.writer, &fields)?;
+        self.writer.write_all(b"}\n")?;
+        self.writer.flush()
+    }
+}
+
+pub fn create_sink<'a, W: Write + 'a>(
+    format: Format,
+    json_bits: JsonBits,
+    width: usize,
+    msb_first: bool,
+    writer: W,
+) -> Box<dyn BitSink + 'a> {
+    match (format, json_bits) {
+        (Format::Json, JsonBits::Array) => Box::new(JsonArraySink {
+            writer,
+            started: false,
+            first: true,
+            buffer: Vec::new(),
+        }),
+        (Format::Json, JsonBits::Base64) => Box::new(PackedSink::new(
+            writer,
+            Encoding::Base64,
+            0,
+            msb_first,
+            true,
+        )),
+        (Format::Raw, _) => Box::new(PackedSink::new(writer, Encoding::Raw, 0, msb_first, false)),
+        (Format::Hex, _) => Box::new(PackedSink::new(
+            writer,
+            Encoding::Hex,
+            width,
+            msb_first,
+            false,
+        )),
+        (Format::Base64, _) => Box::new(PackedSink::new(
+            writer,
+            Encoding::Base64,
+            width,
+            msb_first,
+            false,
+        )),
+        (Format::Ascii01, _) => Box::new(Ascii01Sink {
+            out: LineWriter::new(writer, width),
+            buffer: Vec::new(),
+        }),
+    }
+}
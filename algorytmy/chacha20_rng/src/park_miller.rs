@@ -0,0 +1,149 @@
+use std::io;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::stream::{stream_values, BitSink};
+
+/*
+Park–Miller "Minimal Standard" — natywny odpowiednik Park_Miller.py.
+
+    x_{n+1} = a * x_n mod (2^31 - 1)
+
+Typ publiczny:
+    ParkMiller::new(seed, a)          -- a = 16807 (1988), 48271 lub 69621 (1993)
+    ParkMiller::next_value()          -- kolejny stan (1 ..= m - 1)
+    ParkMiller::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                      -- strumieniowo do BitSink (stała pamięć)
+
+Iloczyn liczony jest metodą Schrage'a (m = a*q + r, r < q), jak w Pythonie
+(_PM_Q, _PM_R), więc żaden wynik pośredni nie przekracza 32 bitów ze znakiem.
+
+Seed jest redukowany modulo m. Seed 0 (także m, 2m, ...) to punkt stały —
+generator zwracałby same zera — więc jest odrzucany błędem. Park_Miller.py
+po cichu zamienia go na 1; dla pozostałych seedów wynik z a = 16807 jest bit
+w bit taki sam jak park_miller_bit_stream(seed, nBits, bitsPerValue, msbFirst).
+
+Funkcja publiczna:
+    park_miller_bit_stream(nBits, bitsPerValue=31, msbFirst=true, seed, a)
+        -> Result<(bity, czas), komunikat> — błąd dla złego seeda, a i bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 31, > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed : u64               -- wartość początkowa, seed mod m != 0
+    a : u64                  -- mnożnik: 16807, 48271 albo 69621
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach albo błąd parametrów.
+*/
+
+// Stałe Park-Millera (Minimal Standard)
+pub const MODULUS: u64 = 2147483647; // 2**31 - 1
+pub const MULTIPLIERS: [u64; 3] = [16807, 48271, 69621];
+pub const DEFAULT_MULTIPLIER: u64 = 16807;
+
+pub const DEFAULT_SEED: u64 = 1;
+pub const DEFAULT_BITS_PER_VALUE: usize = 31;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParkMiller {
+    state: i64,
+    a: i64,
+    q: i64,
+    r: i64,
+}
+
+pub fn validate_parameters(seed: u64, a: u64) -> Result<(), String> {
+    if !MULTIPLIERS.contains(&a) {
+        return Err(format!(
+            "niepoprawny mnożnik Park–Millera: {} (dostępne: 16807, 48271, 69621)",
+            a
+        ));
+    }
+    if seed.is_multiple_of(MODULUS) {
+        return Err(format!(
+            "seed Park–Millera nie może być wielokrotnością m = 2^31 - 1 (podano {}) — \
+             generator utknąłby w zerze",
+            seed
+        ));
+    }
+    Ok(())
+}
+
+impl ParkMiller {
+    pub fn new(seed: u64, a: u64) -> Result<Self, String> {
+        validate_parameters(seed, a)?;
+        let m = MODULUS as i64;
+        let a = a as i64;
+        Ok(ParkMiller {
+            state: (seed % MODULUS) as i64,
+            a,
+            q: m / a,
+            r: m % a,
+        })
+    }
+
+    pub fn next_value(&mut self) -> u64 {
+        // Schrage: a*x mod m = a*(x mod q) - r*(x div q), +m jeśli wynik <= 0
+        let hi = self.state / self.q;
+        let lo = self.state % self.q;
+        let t = self.a * lo - self.r * hi;
+        self.state = if t > 0 { t } else { t + MODULUS as i64 };
+        self.state as u64
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(
+            n_bits,
+            bits_per_value,
+            msb_first,
+            || self.next_value(),
+            sink,
+        )
+    }
+}
+
+pub fn park_miller_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u64,
+    a: u64,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let mut generator = ParkMiller::new(seed, a)?;
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
@@ -0,0 +1,165 @@
+use rand_core::{impls, Error, RngCore, SeedableRng};
+use std::io;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::stream::{stream_values, BitSink};
+
+/*
+PCG32 — implementacja (wariant 32-bitowy XSH-RR), natywny odpowiednik PCG32.py.
+
+Typ publiczny:
+    Pcg32::new(initstate, seq)       -- seedowanie identyczne jak w pcg32_bit_stream
+    Pcg32::next_u32()                -- kolejna wartość 32-bitowa (_pcg_step)
+    Pcg32::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                     -- strumieniowo do BitSink (stała pamięć)
+
+Ekstrakcja bitów jest taka sama jak w Pythonie (_int_to_bits): z każdej
+32-bitowej wartości brane jest bitsPerValue najmłodszych bitów, a dla
+bitsPerValue > 32 brakujące najstarsze bity są zerami. Dla tego samego
+(initstate, seq), bitsPerValue i msbFirst wynik jest bit w bit taki sam
+jak pcg32_bit_stream((initstate, seq), ...).
+
+Funkcja publiczna:
+    pcg32_bit_stream(nBits, bitsPerValue=32, msbFirst=true, initstate, seq)
+        -> Result<(bity, czas), komunikat> — błąd dla bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 32, > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    initstate : u64          -- stan początkowy (seed; w Pythonie domyślnie 1)
+    seq : u64                -- numer strumienia (w Pythonie domyślnie 1)
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach.
+*/
+
+// Stałe PCG
+const PCG_MULT: u64 = 6364136223846793005;
+
+// Wartości domyślne z PCG32.py (seed None -> initstate=1, seq=1)
+pub const DEFAULT_INITSTATE: u64 = 1;
+pub const DEFAULT_SEQ: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    pub fn new(initstate: u64, seq: u64) -> Self {
+        let inc = (seq << 1) | 1;
+        let mut state: u64 = 0;
+        state = state.wrapping_mul(PCG_MULT).wrapping_add(inc);
+        state = state.wrapping_add(initstate);
+        state = state.wrapping_mul(PCG_MULT).wrapping_add(inc);
+        Pcg32 { state, inc }
+    }
+
+    /*
+    Krok generatora jak _pcg_step: przesunięcie LCG, potem permutacja XSH-RR.
+    Tak jak w PCG32.py permutowany jest już nowy stan (pcg32_random_r z pcg-c
+    bierze stary), więc strumień jest przesunięty o jedną wartość względem C.
+    */
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(PCG_MULT).wrapping_add(self.inc);
+        let x = self.state;
+        let xorshifted = (((x >> 18) ^ x) >> 27) as u32;
+        let rot = (x >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(
+            n_bits,
+            bits_per_value,
+            msb_first,
+            || self.next_u32() as u64,
+            sink,
+        )
+    }
+}
+
+impl RngCore for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        Pcg32::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Pcg32 {
+    // initstate i seq zapisane little-endian
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut initstate = [0u8; 8];
+        let mut seq = [0u8; 8];
+        initstate.copy_from_slice(&seed[..8]);
+        seq.copy_from_slice(&seed[8..]);
+        Self::new(u64::from_le_bytes(initstate), u64::from_le_bytes(seq))
+    }
+
+    // Tak jak pcg32_bit_stream(seed: int): seed to initstate, seq = 1
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state, DEFAULT_SEQ)
+    }
+}
+
+pub fn pcg32_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    initstate: u64,
+    seq: u64,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let bpv = bits_per_value.unwrap_or(32);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut generator = Pcg32::new(initstate, seq);
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
@@ -0,0 +1,195 @@
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+use rand_core::RngCore;
+
+use crate::chacha::ChaCha20Generator;
+
+/*
+Arytmetyka liczb pierwszych dla parametrów generatorów.
+
+Funkcje publiczne:
+    gcd(a, b)               -- największy wspólny dzielnik
+    is_prime(n)             -- deterministyczny test Millera–Rabina dla n < 2^64
+    is_probable_prime(n)    -- Miller–Rabin dla dowolnie dużego n (BigUint):
+                               deterministyczny poniżej 2^64, powyżej MR_ROUNDS rund
+    prime_factors(n)        -- różne czynniki pierwsze n <= 2^64, rosnąco
+                               (Pollard rho w wariancie Brenta + Miller–Rabin)
+
+Używane do sprawdzania warunków Hulla–Dobella w LCG (czynniki pierwsze modułu)
+i liczb pierwszych Bluma w BBS.
+*/
+
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+// Te bazy dają wynik deterministyczny dla każdego n < 2^64
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // n - 1 = d * 2^r, d nieparzyste
+    let r = (n - 1).trailing_zeros();
+    let d = (n - 1) >> r;
+
+    'witness: for &a in &WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..r {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/*
+Rundy Millera–Rabina dla n >= 2^64: pierwsza z bazą 2, kolejne z bazami
+losowanymi z [2, n - 2] przez ChaCha20 o stałym kluczu (wynik powtarzalny).
+Złożona liczba przechodzi pojedynczą rundę z prawdopodobieństwem <= 1/4.
+*/
+pub const MR_ROUNDS: usize = 40;
+
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    if let Some(small) = n.to_u64() {
+        return is_prime(small);
+    }
+    for p in WITNESSES {
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+
+    // n - 1 = d * 2^r, d nieparzyste
+    let n_minus_1 = n - 1u32;
+    let r = n_minus_1.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_1 >> r;
+
+    let span = n - 3u32;
+    let mut rng = ChaCha20Generator::from_seed_u64(0);
+    // 8 nadmiarowych bajtów, żeby redukcja modulo span była prawie jednostajna
+    let mut bytes = vec![0u8; n.bits().div_ceil(8) as usize + 8];
+
+    'witness: for round in 0..MR_ROUNDS {
+        let a = if round == 0 {
+            BigUint::from(2u32)
+        } else {
+            rng.fill_bytes(&mut bytes);
+            BigUint::from_bytes_le(&bytes) % &span + 2u32
+        };
+
+        let mut x = a.modpow(&d, n);
+        if x.is_one() || x == n_minus_1 {
+            continue;
+        }
+        for _ in 1..r {
+            x = &x * &x % n;
+            if x == n_minus_1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// Nietrywialny dzielnik złożonego, nieparzystego n (Pollard rho, wariant Brenta)
+fn pollard_rho(n: u64) -> u64 {
+    let mut increment = 1u64;
+    loop {
+        let f = |x: u64| ((mul_mod(x, x, n) as u128 + increment as u128) % n as u128) as u64;
+        let mut x = 2u64;
+        let mut y = x;
+        let mut divisor = 1u64;
+        let mut power = 1u64;
+        let mut steps = 0u64;
+
+        while divisor == 1 {
+            if steps == power {
+                x = y;
+                power *= 2;
+                steps = 0;
+            }
+            y = f(y);
+            steps += 1;
+            divisor = gcd(x.abs_diff(y) as u128, n as u128) as u64;
+        }
+
+        if divisor != n {
+            return divisor;
+        }
+        // Cykl bez dzielnika — inna funkcja x^2 + c
+        increment += 1;
+    }
+}
+
+fn collect_factors(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let divisor = pollard_rho(n);
+    collect_factors(divisor, factors);
+    collect_factors(n / divisor, factors);
+}
+
+pub fn prime_factors(n: u128) -> Vec<u64> {
+    assert!(
+        n > 0 && n <= 1u128 << 64,
+        "prime_factors obsługuje 1 <= n <= 2^64"
+    );
+
+    let mut factors = Vec::new();
+    let mut n = n;
+
+    // Małe czynniki od razu; po usunięciu dwójek n mieści się w u64
+    for p in WITNESSES {
+        if n.is_multiple_of(p as u128) {
+            factors.push(p);
+            while n.is_multiple_of(p as u128) {
+                n /= p as u128;
+            }
+        }
+    }
+
+    collect_factors(n as u64, &mut factors);
+    factors.sort_unstable();
+    factors.dedup();
+    factors
+}
@@ -0,0 +1,139 @@
+use rand_core::{impls, Error, RngCore, SeedableRng};
+use std::io;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::stream::{stream_values, BitSink};
+
+/*
+SplitMix64 — natywny odpowiednik SplitMix64.py (_splitmix64_next).
+
+Typ publiczny:
+    SplitMix64::new(seed)            -- stan początkowy = seed (64-bit)
+    SplitMix64::next_u64()           -- kolejna wartość 64-bitowa
+    SplitMix64::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                     -- strumieniowo do BitSink (stała pamięć)
+
+Ten sam generator służy jako KDF seed -> klucz ChaCha20 (chacha::derive_key)
+i później do seedowania innych generatorów z jednej liczby 64-bitowej.
+
+Funkcja publiczna:
+    splitmix64_bit_stream(nBits, bitsPerValue=64, msbFirst=true, seed)
+        -> Result<(bity, czas), komunikat> — błąd dla bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 64, > 0);
+                                dla bitsPerValue > 64 najstarsze bity są zerami
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed : u64               -- stan początkowy (w Pythonie seed None -> 1)
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach; wynik jest bit w bit
+taki sam jak splitmix64_bit_stream(seed, nBits, bitsPerValue, msbFirst).
+*/
+
+// Stała przyrostu SplitMix64 (2^64 / złoty podział)
+const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// Seed None w SplitMix64.py
+pub const DEFAULT_SEED: u64 = 1;
+pub const DEFAULT_BITS_PER_VALUE: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(n_bits, bits_per_value, msb_first, || self.next_u64(), sink)
+    }
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        SplitMix64::next_u64(self) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        SplitMix64::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+pub fn splitmix64_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u64,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut generator = SplitMix64::new(seed);
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
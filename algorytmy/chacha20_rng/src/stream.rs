@@ -0,0 +1,103 @@
+use serde_json::{Map, Value};
+use std::io;
+
+use crate::bits::{check_bits_per_value, int_to_bits_into, PackedBits};
+
+/*
+Strumieniowa generacja bitów o stałym zużyciu pamięci.
+
+    BitSink                -- odbiorca bitów (serializery z output.rs)
+    stream_bits(nBits, nextBits, sink)
+                           -- nextBits dopisuje do bufora bity jednej wartości
+    stream_values(nBits, bitsPerValue, msbFirst, nextValue, sink)
+                           -- to samo dla generatorów zwracających wartości u64
+
+Kolejne wartości z generatora przechodzą przez ekstrakcję bitów (int_to_bits)
+i trafiają do odbiorcy paczkami po BATCH_BITS bitów, więc pamięć nie zależy
+od nBits, a wynik pojawia się na wyjściu na bieżąco. bitsPerValue = 0
+(wartość bez bitów) kończy się błędem InvalidInput, a nie pętlą bez końca.
+*/
+
+// Liczba bitów przekazywanych do odbiorcy w jednej paczce
+pub const BATCH_BITS: usize = 1 << 16;
+
+pub trait BitSink {
+    // Przyjmuje kolejne bity (wartości 0/1) w kolejności strumienia
+    fn write_bits(&mut self, bits: &[u8]) -> io::Result<()>;
+
+    // Kończy zapis; formaty z metadanymi (JSON) dopisują czas i `metadata`
+    fn finish(&mut self, elapsed: f64, metadata: Map<String, Value>) -> io::Result<()>;
+}
+
+// Odbiorca zbierający bity w pamięci (extend_bits generatorów i testy)
+impl BitSink for Vec<u8> {
+    fn write_bits(&mut self, bits: &[u8]) -> io::Result<()> {
+        self.extend_from_slice(bits);
+        Ok(())
+    }
+
+    fn finish(&mut self, _elapsed: f64, _metadata: Map<String, Value>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Odbiorca pakujący bity po 64 w słowie (podkomendy test i linear-complexity)
+impl BitSink for PackedBits {
+    fn write_bits(&mut self, bits: &[u8]) -> io::Result<()> {
+        self.extend_from_bits(bits);
+        Ok(())
+    }
+
+    fn finish(&mut self, _elapsed: f64, _metadata: Map<String, Value>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+pub fn stream_bits<F>(n_bits: u64, mut next_bits: F, sink: &mut dyn BitSink) -> io::Result<()>
+where
+    F: FnMut(&mut Vec<u8>),
+{
+    let mut batch: Vec<u8> = Vec::with_capacity(BATCH_BITS);
+    let mut remaining = n_bits;
+
+    while remaining > 0 {
+        batch.clear();
+        while batch.len() < BATCH_BITS && (batch.len() as u64) < remaining {
+            let before = batch.len();
+            next_bits(&mut batch);
+            if batch.len() == before {
+                // Wartość o zerowej szerokości — pętla nigdy by się nie skończyła
+                return check_bits_per_value(0).map_err(invalid_input);
+            }
+        }
+
+        // Ostatnia wartość może być przycięta do brakującej liczby bitów
+        let take = batch.len().min(remaining.min(usize::MAX as u64) as usize);
+        sink.write_bits(&batch[..take])?;
+        remaining -= take as u64;
+    }
+
+    Ok(())
+}
+
+pub fn stream_values<F>(
+    n_bits: u64,
+    bits_per_value: usize,
+    msb_first: bool,
+    mut next_value: F,
+    sink: &mut dyn BitSink,
+) -> io::Result<()>
+where
+    F: FnMut() -> u64,
+{
+    check_bits_per_value(bits_per_value).map_err(invalid_input)?;
+    stream_bits(
+        n_bits,
+        |batch| int_to_bits_into(next_value(), bits_per_value, msb_first, batch),
+        sink,
+    )
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::time::Instant;
+
+use crate::bits::{ByteExtractor, Extraction};
+use crate::stream::{stream_bits, BitSink};
+
+/*
+Systemowy CSPRNG — natywny odpowiednik SystemRNG.py (entropia z jądra).
+
+Źródła (EntropySource):
+    GetRandom   -- wywołanie systemowe getrandom(2) (Linux >= 3.17)
+    DevUrandom  -- odczyt z /dev/urandom (fallback: starsze jądro, seccomp
+                   blokujący getrandom, inne systemy uniksowe)
+
+SystemRng::new() wybiera getrandom(2), jeśli jest dostępne, w przeciwnym razie
+/dev/urandom. Windows (BCryptGenRandom w Pythonie) nie jest tu obsługiwany.
+
+Typ publiczny:
+    SystemRng::new()                  -- automatyczny wybór źródła
+    SystemRng::with_source(source)    -- wymuszone źródło (np. test fallbacku)
+    SystemRng::fill_bytes(buf)        -- kolejne bajty entropii
+    SystemRng::stream_bits(nBits, bitsPerValue, msbFirst, extraction, sink)
+                                      -- strumieniowo do BitSink (stała pamięć)
+
+Bity są wyciągane z bajtów tą samą ścieżką co w ChaCha20 (bits::ByteExtractor,
+tryby truncate i contiguous): w trybie truncate każda wartość to ceil(bpv / 8)
+bajtów big-endian obciętych do bpv najmłodszych bitów, jak w SystemRNG.py.
+Seed jest ignorowany — wyniku nie da się odtworzyć.
+
+Funkcja publiczna:
+    system_bit_stream(nBits, bitsPerValue=32, msbFirst=true) -> (bity, czas)
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 32, > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach albo błąd źródła entropii.
+*/
+
+pub const DEFAULT_BITS_PER_VALUE: usize = 32;
+
+// Rozmiar bufora bajtów pobieranych z jądra jednym wywołaniem
+const ENTROPY_BUFFER: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropySource {
+    GetRandom,
+    DevUrandom,
+}
+
+impl fmt::Display for EntropySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EntropySource::GetRandom => "getrandom",
+            EntropySource::DevUrandom => "/dev/urandom",
+        };
+        f.write_str(name)
+    }
+}
+
+// Jedno wywołanie getrandom(2); EINTR jest powtarzane
+#[cfg(target_os = "linux")]
+fn getrandom_fill(mut dest: &mut [u8]) -> io::Result<()> {
+    while !dest.is_empty() {
+        let result = unsafe { libc::getrandom(dest.as_mut_ptr().cast(), dest.len(), 0) };
+        if result < 0 {
+            let error = io::Error::last_os_error();
+            if error.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(error);
+        }
+        dest = &mut dest[result as usize..];
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn getrandom_fill(_dest: &mut [u8]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "getrandom(2) jest dostępne tylko na Linuksie",
+    ))
+}
+
+enum Reader {
+    GetRandom,
+    DevUrandom(File),
+}
+
+pub struct SystemRng {
+    reader: Reader,
+    buffer: [u8; ENTROPY_BUFFER],
+    position: usize,
+    extractor: ByteExtractor,
+}
+
+impl SystemRng {
+    pub fn new() -> io::Result<Self> {
+        Self::with_source(EntropySource::GetRandom)
+            .or_else(|_| Self::with_source(EntropySource::DevUrandom))
+    }
+
+    pub fn with_source(source: EntropySource) -> io::Result<Self> {
+        let reader = match source {
+            EntropySource::GetRandom => {
+                // Próbne wywołanie: ENOSYS / EPERM oznaczają brak getrandom(2)
+                getrandom_fill(&mut [0u8; 1])?;
+                Reader::GetRandom
+            }
+            EntropySource::DevUrandom => Reader::DevUrandom(File::open("/dev/urandom")?),
+        };
+        Ok(SystemRng {
+            reader,
+            buffer: [0u8; ENTROPY_BUFFER],
+            position: ENTROPY_BUFFER,
+            extractor: ByteExtractor::new(),
+        })
+    }
+
+    pub fn source(&self) -> EntropySource {
+        match self.reader {
+            Reader::GetRandom => EntropySource::GetRandom,
+            Reader::DevUrandom(_) => EntropySource::DevUrandom,
+        }
+    }
+
+    // Nadpisuje bufor kolejnymi bajtami entropii
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) -> io::Result<()> {
+        fill_buffered(&mut self.reader, &mut self.buffer, &mut self.position, dest)
+    }
+
+    // Bity kolejnej wartości w wybranym trybie ekstrakcji (bits::ByteExtractor)
+    pub fn next_bits(
+        &mut self,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let (reader, buffer, position) = (&mut self.reader, &mut self.buffer, &mut self.position);
+        let start = out.len();
+        let mut result = Ok(());
+        self.extractor.next_bits(
+            |dest| {
+                if result.is_ok() {
+                    result = fill_buffered(reader, buffer, position, dest);
+                }
+            },
+            bits_per_value,
+            msb_first,
+            extraction,
+            out,
+        );
+        // Po błędzie źródła nie zostawiamy bitów z nieuzupełnionego bufora
+        if result.is_err() {
+            out.truncate(start);
+        }
+        result
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc błąd źródła wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, extraction, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        extraction: Extraction,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        // Błąd źródła kończy strumień (next_bits nie dopisuje wtedy bitów),
+        // a zwracany jest on zamiast ogólnego błędu stream_bits
+        let mut result = Ok(());
+        let streamed = stream_bits(
+            n_bits,
+            |batch| {
+                if result.is_ok() {
+                    result = self.next_bits(bits_per_value, msb_first, extraction, batch);
+                }
+            },
+            sink,
+        );
+        result?;
+        streamed
+    }
+}
+
+fn fill_buffered(
+    reader: &mut Reader,
+    buffer: &mut [u8; ENTROPY_BUFFER],
+    position: &mut usize,
+    mut dest: &mut [u8],
+) -> io::Result<()> {
+    // Entropia jest pobierana paczkami — pojedyncze wywołania dla kilku
+    // bajtów byłyby kosztowne
+    while !dest.is_empty() {
+        if *position == ENTROPY_BUFFER {
+            match reader {
+                Reader::GetRandom => getrandom_fill(buffer)?,
+                Reader::DevUrandom(file) => file.read_exact(buffer)?,
+            }
+            *position = 0;
+        }
+        let take = (ENTROPY_BUFFER - *position).min(dest.len());
+        dest[..take].copy_from_slice(&buffer[*position..*position + take]);
+        *position += take;
+        dest = &mut dest[take..];
+    }
+    Ok(())
+}
+
+pub fn system_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+) -> io::Result<(Vec<u8>, f64)> {
+    let start = Instant::now();
+
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE);
+    let mut generator = SystemRng::new()?;
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator.extend_bits(&mut output, n_bits, bpv, msb_first, Extraction::Truncate)?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
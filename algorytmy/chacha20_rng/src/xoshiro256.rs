@@ -0,0 +1,214 @@
+use rand_core::{impls, Error, RngCore, SeedableRng};
+use std::io;
+use std::time::Instant;
+
+use crate::bits::check_bits_per_value;
+use crate::splitmix64::SplitMix64;
+use crate::stream::{stream_values, BitSink};
+
+/*
+Xoshiro256** — generator Blackmana i Vigny (xoshiro256starstar.c, wersja 1.0).
+
+Typ publiczny:
+    Xoshiro256StarStar::new(seed)        -- stan z czterech wyjść SplitMix64(seed)
+    Xoshiro256StarStar::from_state(s)    -- jawny stan 4 x u64 (nie same zera)
+    Xoshiro256StarStar::next_u64()       -- kolejna wartość 64-bitowa
+    Xoshiro256StarStar::jump()           -- skok o 2^128 kroków (2^128 niezależnych podciągów)
+    Xoshiro256StarStar::long_jump()      -- skok o 2^192 kroków (2^64 punktów startowych)
+    Xoshiro256StarStar::stream_bits(nBits, bitsPerValue, msbFirst, sink)
+                                         -- strumieniowo do BitSink (stała pamięć)
+
+Seedowanie jak zaleca autor i jak robi rand_xoshiro::seed_from_u64: stan to
+cztery kolejne wyjścia SplitMix64 zainicjalizowanego seedem, więc sąsiednie
+seedy dają niezależne stany, a stan nigdy nie jest zerowy w praktyce.
+
+Uwaga: Xorshift256/Xoshiro256.cs mimo nazwy nie jest xoshiro, tylko nakładką
+na systemowy CSPRNG (seed jest ignorowany), więc nie ma wektorów do porównania.
+Poprawność sprawdzają wektory referencyjnej implementacji w C (tests/xoshiro256.rs).
+
+Funkcja publiczna:
+    xoshiro256_bit_stream(nBits, bitsPerValue=64, msbFirst=true, seed)
+        -> Result<(bity, czas), komunikat> — błąd dla bitsPerValue = 0
+
+Parametry:
+    nBits : usize            -- liczba bitów do zwrócenia
+    bitsPerValue : usize     -- ile bitów pobrać z każdej wartości (domyślnie 64, > 0)
+    msbFirst : bool          -- True: MSB-first, False: LSB-first
+    seed : u64               -- seed dla SplitMix64
+
+Zwraca wektor bitów 0/1 i czas wykonania w sekundach.
+*/
+
+pub const DEFAULT_SEED: u64 = 0;
+pub const DEFAULT_BITS_PER_VALUE: usize = 64;
+
+// Wielomiany skoków z xoshiro256starstar.c
+const JUMP: [u64; 4] = [
+    0x180e_c6d3_3cfd_0aba,
+    0xd5a6_1266_f0c9_392c,
+    0xa958_2618_e03f_c9aa,
+    0x39ab_dc45_29b1_661c,
+];
+const LONG_JUMP: [u64; 4] = [
+    0x76e1_5d3e_fefd_cbbf,
+    0xc500_4e44_1c52_2fb3,
+    0x7771_0069_854e_e241,
+    0x3910_9bb0_2acb_e635,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xoshiro256StarStar {
+    s: [u64; 4],
+}
+
+impl Xoshiro256StarStar {
+    pub fn new(seed: u64) -> Self {
+        let mut splitmix = SplitMix64::new(seed);
+        let mut s = [0u64; 4];
+        for word in s.iter_mut() {
+            *word = splitmix.next_u64();
+        }
+        Xoshiro256StarStar { s }
+    }
+
+    pub fn from_state(s: [u64; 4]) -> Self {
+        // Ze stanu zerowego generator zwraca same zera
+        assert!(s != [0; 4], "stan xoshiro256** nie może być zerowy");
+        Xoshiro256StarStar { s }
+    }
+
+    pub fn state(&self) -> &[u64; 4] {
+        &self.s
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    pub fn jump(&mut self) {
+        self.apply_jump(&JUMP);
+    }
+
+    pub fn long_jump(&mut self) {
+        self.apply_jump(&LONG_JUMP);
+    }
+
+    // Stan po skoku to kombinacja liniowa (XOR) stanów wybranych bitami wielomianu
+    fn apply_jump(&mut self, polynomial: &[u64; 4]) {
+        let mut s = [0u64; 4];
+        for word in polynomial {
+            for bit in 0..64 {
+                if word & (1u64 << bit) != 0 {
+                    for (acc, current) in s.iter_mut().zip(self.s.iter()) {
+                        *acc ^= current;
+                    }
+                }
+                self.next_u64();
+            }
+        }
+        self.s = s;
+    }
+
+    // Dopisuje do `out` kolejne bity strumienia, aż będzie ich n_bits
+    pub fn extend_bits(
+        &mut self,
+        out: &mut Vec<u8>,
+        n_bits: usize,
+        bits_per_value: usize,
+        msb_first: bool,
+    ) -> io::Result<()> {
+        // `out` jest odbiorcą stream_bits, więc zerowa szerokość wraca tak samo jak tam
+        let missing = n_bits.saturating_sub(out.len()) as u64;
+        self.stream_bits(missing, bits_per_value, msb_first, out)
+    }
+
+    // Strumieniowa wersja extend_bits: bity trafiają do `sink` paczkami
+    pub fn stream_bits(
+        &mut self,
+        n_bits: u64,
+        bits_per_value: usize,
+        msb_first: bool,
+        sink: &mut dyn BitSink,
+    ) -> io::Result<()> {
+        stream_values(n_bits, bits_per_value, msb_first, || self.next_u64(), sink)
+    }
+}
+
+impl RngCore for Xoshiro256StarStar {
+    // Jak w rand_xoshiro: starsze 32 bity są lepszej jakości
+    fn next_u32(&mut self) -> u32 {
+        (Xoshiro256StarStar::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Xoshiro256StarStar::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStar {
+    // Stan 4 x u64 zapisany little-endian
+    type Seed = [u8; 32];
+
+    // Seed z samych zer zastępowany jest seedowaniem przez SplitMix64(0)
+    fn from_seed(seed: Self::Seed) -> Self {
+        if seed == [0u8; 32] {
+            return Self::new(0);
+        }
+        let mut s = [0u64; 4];
+        for (word, chunk) in s.iter_mut().zip(seed.chunks_exact(8)) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            *word = u64::from_le_bytes(bytes);
+        }
+        Self::from_state(s)
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+pub fn xoshiro256_bit_stream(
+    n_bits: usize,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    seed: u64,
+) -> Result<(Vec<u8>, f64), String> {
+    let start = Instant::now();
+
+    let bpv = bits_per_value.unwrap_or(DEFAULT_BITS_PER_VALUE);
+    check_bits_per_value(bpv)?;
+    if n_bits == 0 {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let mut generator = Xoshiro256StarStar::new(seed);
+
+    let mut output = Vec::with_capacity(n_bits);
+    generator
+        .extend_bits(&mut output, n_bits, bpv, msb_first)
+        .map_err(|err| err.to_string())?;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok((output, elapsed))
+}
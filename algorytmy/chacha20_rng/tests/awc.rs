@@ -0,0 +1,189 @@
+/*
+AWC / SWB: zgodność z AWCG.py (tests/data/awc_python.txt — pierwsze 10 000 bitów
+z awcg_bit_stream(seed, 10000, r=r, s=s, base=base, bits_per_value=bpv,
+msb_first=msb) dla różnych opóźnień i baz, także nie będących potęgą dwójki
+i 2^64), seed-sekwencja, wariant subtract-with-borrow oraz walidacja r > s > 0 i bits_per_value > 0.
+*/
+
+mod common;
+
+use chacha20_rng::awc::{self, simple_seed_state};
+use chacha20_rng::{awc_bit_stream, Awc, AwcVariant};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, optional, run_rng};
+use serde_json::Value;
+
+const FIXTURES: &str = include_str!("data/awc_python.txt");
+
+struct Case {
+    seed: u64,
+    r: usize,
+    s: usize,
+    base: u128,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        awc_bit_stream(
+            self.n_bits,
+            self.bits_per_value,
+            self.msb_first,
+            self.seed,
+            self.r,
+            self.s,
+            self.base,
+            AwcVariant::AddWithCarry,
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            seed: fields[0].parse().unwrap(),
+            r: fields[1].parse().unwrap(),
+            s: fields[2].parse().unwrap(),
+            base: fields[3].parse().unwrap(),
+            bits_per_value: optional(fields[4]),
+            msb_first: fields[5] == "true",
+            n_bits: fields[6].parse().unwrap(),
+            hex: fields[7],
+        })
+        .collect()
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!(
+                "seed {} r {} s {} base {}",
+                case.seed, case.r, case.s, case.base
+            ),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    for case in cases() {
+        let options = format!(
+            "--algorithm awc --seed {} --r {} --s {} --base {}",
+            case.seed, case.r, case.s, case.base
+        );
+        assert_cli_output(&options, case.bits_per_value, case.msb_first, &case.bits());
+    }
+}
+
+#[test]
+fn explicit_state_matches_python_sequence_seed() {
+    // awcg_bit_stream([5, 6, 7], 64, r=5, s=2, base=2**8, bits_per_value=8):
+    // brakujące dwie wartości stanu dopełnia _simple_seed_state(1, 2, 256)
+    let mut generator = Awc::from_state(&[5, 6, 7], 5, 2, 256, AwcVariant::AddWithCarry).unwrap();
+    let values: Vec<u64> = (0..8).map(|_| generator.next_value()).collect();
+    assert_eq!(values, [0xab, 0xed, 0xb2, 0x93, 0x9a, 0x3f, 0x88, 0xf2]);
+
+    // Seed-liczba to ten sam stan co jawna lista z simple_seed_state
+    let state = simple_seed_state(42, 24, 1 << 32);
+    let mut from_seed = Awc::new(42, 24, 10, 1 << 32, AwcVariant::AddWithCarry).unwrap();
+    let mut from_state =
+        Awc::from_state(&state, 24, 10, 1 << 32, AwcVariant::AddWithCarry).unwrap();
+    for _ in 0..1000 {
+        assert_eq!(from_seed.next_value(), from_state.next_value());
+    }
+}
+
+#[test]
+fn subtract_with_borrow_matches_recurrence() {
+    for (r, s, base) in [
+        (24usize, 10usize, 1u128 << 32),
+        (5, 2, 10),
+        (10, 3, 1 << 64),
+    ] {
+        let mut generator = Awc::new(7, r, s, base, AwcVariant::SubtractWithBorrow).unwrap();
+
+        // Bezpośrednio z definicji: x_n = x_{n-s} - x_{n-r} - c (mod base)
+        let mut x: Vec<i128> = simple_seed_state(7, r, base)
+            .into_iter()
+            .map(|value| value as i128)
+            .collect();
+        let mut borrow = 0;
+        for n in r..r + 5000 {
+            let mut value = x[n - s] - x[n - r] - borrow;
+            borrow = (value < 0) as i128;
+            if value < 0 {
+                value += base as i128;
+            }
+            x.push(value);
+            assert_eq!(generator.next_value() as i128, value, "r {} s {}", r, s);
+        }
+    }
+}
+
+#[test]
+fn invalid_lags_and_base_are_rejected() {
+    for (r, s, base) in [
+        (10, 10, 1 << 32),
+        (10, 0, 1 << 32),
+        (3, 5, 1 << 32),
+        (24, 10, 1),
+        (24, 10, (1 << 64) + 1),
+    ] {
+        assert!(
+            Awc::new(1, r, s, base, AwcVariant::AddWithCarry).is_err(),
+            "r {} s {} base {}",
+            r,
+            s,
+            base
+        );
+    }
+    assert_eq!(awc::default_bits_per_value(1 << 32), 32);
+    assert_eq!(awc::default_bits_per_value(1000003), 20);
+
+    for args in [
+        ["--r", "10", "--s", "10"],
+        ["--r", "5", "--s", "0"],
+        ["--base", "1", "--s", "3"],
+        ["--variant", "xyz", "--s", "3"],
+    ] {
+        let mut full = vec!["--algorithm", "awc"];
+        full.extend(args);
+        let output = run_rng(&full);
+        assert!(!output.status.success(), "{:?}", args);
+    }
+
+    let output = run_rng(&["--algorithm", "lcg", "--r", "24"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn json_reports_parameters() {
+    let output = run_rng(&["--algorithm", "awc", "--variant", "swb", "--base", "2^64"]);
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["algorithm"].as_str(), Some("awc"));
+    assert_eq!(json["variant"].as_str(), Some("swb"));
+    assert_eq!(json["r"].as_u64(), Some(24));
+    assert_eq!(json["s"].as_u64(), Some(10));
+    assert_eq!(json["base"].as_str(), Some("18446744073709551616"));
+    assert_eq!(json["bits"].as_array().unwrap().len(), 200);
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    let variant = AwcVariant::AddWithCarry;
+    assert!(awc_bit_stream(100, Some(0), true, 1, 24, 10, 1 << 32, variant).is_err());
+
+    let mut generator = Awc::new(1, 24, 10, 1 << 32, variant).unwrap();
+    let mut bits = Vec::new();
+    assert!(generator.extend_bits(&mut bits, 100, 0, true).is_err());
+}
@@ -0,0 +1,197 @@
+/*
+BBS: zgodność z BlumBlumShub.py (tests/data/bbs_python.txt — bbs_bit_stream(seed,
+n_bits, p=p, q=q, bits_per_value=bpv, msb_first=msb) dla domyślnych p = 383,
+q = 503, modułów 64- i 128-bitowych oraz liczb pierwszych Bluma po 512 bitów),
+test Millera–Rabina dla BigUint i odrzucanie niepoprawnych p, q, seeda
+i bits_per_value.
+*/
+
+mod common;
+
+use chacha20_rng::bbs::{self, max_bits_per_value};
+use chacha20_rng::primes::is_probable_prime;
+use chacha20_rng::{bbs_bit_stream, Bbs};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, run_rng};
+use num_bigint::BigUint;
+use serde_json::Value;
+
+const FIXTURES: &str = include_str!("data/bbs_python.txt");
+
+struct Case {
+    seed: BigUint,
+    p: BigUint,
+    q: BigUint,
+    bits_per_value: usize,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        bbs_bit_stream(
+            self.n_bits,
+            Some(self.bits_per_value),
+            self.msb_first,
+            &self.seed,
+            &self.p,
+            &self.q,
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            seed: fields[0].parse().unwrap(),
+            p: fields[1].parse().unwrap(),
+            q: fields[2].parse().unwrap(),
+            bits_per_value: fields[3].parse().unwrap(),
+            msb_first: fields[4] == "true",
+            n_bits: fields[5].parse().unwrap(),
+            hex: fields[6],
+        })
+        .collect()
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!(
+                "seed {} p {} bpv {}",
+                case.seed, case.p, case.bits_per_value
+            ),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    // --seed w CLI to u64, więc tylko przypadki z takim ziarnem; q szesnastkowo
+    for case in cases().into_iter().filter(|case| case.seed.bits() <= 64) {
+        let options = format!(
+            "--algorithm bbs --seed {} --p {} --q 0x{:x}",
+            case.seed, case.p, case.q
+        );
+        assert_cli_output(
+            &options,
+            Some(case.bits_per_value),
+            case.msb_first,
+            &case.bits(),
+        );
+    }
+}
+
+#[test]
+fn miller_rabin_on_big_integers() {
+    // 2^127 - 1 i 2^521 - 1 (liczby pierwsze Mersenne'a)
+    assert!(is_probable_prime(&((BigUint::from(1u32) << 127) - 1u32)));
+    assert!(is_probable_prime(&((BigUint::from(1u32) << 521) - 1u32)));
+    // 2^128 + 1 = 59649589127497217 * 5704689200685129054721
+    assert!(!is_probable_prime(&((BigUint::from(1u32) << 128) + 1u32)));
+    // Iloczyn dwóch 512-bitowych liczb pierwszych z danych testowych
+    let case = &cases()[5];
+    assert!(is_probable_prime(&case.p) && is_probable_prime(&case.q));
+    assert!(!is_probable_prime(&(&case.p * &case.q)));
+    // Liczby Carmichaela (małe — ścieżka deterministyczna)
+    assert!(!is_probable_prime(&BigUint::from(8911u32)));
+    assert!(!is_probable_prime(&BigUint::from(3215031751u64)));
+}
+
+#[test]
+fn max_bits_per_value_is_floor_log2_log2_n() {
+    // N = 383 * 503 = 192649: log2 N ≈ 17.56, log2 17.56 ≈ 4.13
+    assert_eq!(max_bits_per_value(&BigUint::from(192649u32)), 4);
+    // 2^16 <= N < 2^17: floor(log2 N) = 16 -> 4
+    assert_eq!(max_bits_per_value(&BigUint::from(1u32 << 16)), 4);
+    assert_eq!(max_bits_per_value(&BigUint::from((1u32 << 16) - 1)), 3);
+    assert_eq!(max_bits_per_value(&(BigUint::from(1u32) << 1024)), 10);
+    assert_eq!(max_bits_per_value(&(BigUint::from(1u32) << 1023)), 9);
+}
+
+#[test]
+fn invalid_parameters_are_rejected() {
+    let seed = BigUint::from(bbs::DEFAULT_SEED);
+    for (p, q) in [
+        (383u32, 383u32), // p == q
+        (389, 503),       // 389 ≡ 1 (mod 4)
+        (3, 503),         // p <= 3
+        (387, 503),       // 387 = 3^2 * 43 ≡ 3 (mod 4), ale złożona
+    ] {
+        assert!(
+            Bbs::new(&BigUint::from(p), &BigUint::from(q), &seed).is_err(),
+            "p {} q {}",
+            p,
+            q
+        );
+    }
+
+    let (p, q) = (BigUint::from(383u32), BigUint::from(503u32));
+    // gcd(seed, N) != 1 oraz seed^2 ≡ 1 (mod N)
+    for seed in [0u32, 383, 503 * 2, 1, 192648] {
+        assert!(
+            Bbs::new(&p, &q, &BigUint::from(seed)).is_err(),
+            "seed {}",
+            seed
+        );
+    }
+    assert!(bbs_bit_stream(100, Some(5), true, &seed, &p, &q).is_err());
+    assert!(bbs_bit_stream(100, Some(0), true, &seed, &p, &q).is_err());
+    let mut generator = Bbs::new(&p, &q, &seed).unwrap();
+    assert!(generator
+        .extend_bits(&mut Vec::new(), 100, 0, true)
+        .is_err());
+    assert!(bbs_bit_stream(100, Some(4), true, &seed, &p, &q).is_ok());
+
+    for args in [
+        vec!["--p", "389"],
+        vec!["--q", "387"],
+        vec!["--p", "xyz"],
+        vec!["--seed", "766"],
+        vec!["--bits-per-value", "5"],
+    ] {
+        let mut full = vec!["--algorithm", "bbs"];
+        full.extend(&args);
+        assert!(!run_rng(&full).status.success(), "{:?}", args);
+    }
+    assert!(!run_rng(&["--p", "383"]).status.success());
+}
+
+#[test]
+fn json_reports_parameters() {
+    let case = &cases()[5];
+    let (p, q) = (case.p.to_string(), case.q.to_string());
+    let output = run_rng(&[
+        "--algorithm",
+        "bbs",
+        "--p",
+        &p,
+        "--q",
+        &q,
+        "--bits-per-value",
+        "9",
+    ]);
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["algorithm"].as_str(), Some("bbs"));
+    assert_eq!(json["initial_state"].as_u64(), Some(bbs::DEFAULT_SEED));
+    assert_eq!(json["p"].as_str(), Some(case.p.to_string().as_str()));
+    assert_eq!(
+        json["n"].as_str(),
+        Some((&case.p * &case.q).to_string().as_str())
+    );
+    assert_eq!(json["max_bits_per_value"].as_u64(), Some(9));
+    assert_eq!(json["bits"].as_array().unwrap().len(), 200);
+
+    let output = run_rng(&["--algorithm", "bbs"]);
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["n"].as_u64(), Some(192649));
+}
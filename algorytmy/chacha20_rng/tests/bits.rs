@@ -0,0 +1,102 @@
+use chacha20_rng::bits::{bit_chunks, BitSequence, PackedBits};
+use chacha20_rng::output::pack_bits;
+use chacha20_rng::{bits_to_int, int_to_bits, ChaCha20Generator};
+use rand_core::{RngCore, SeedableRng};
+
+fn sample_values(width: usize) -> Vec<u64> {
+    let mask = if width == 64 {
+        u64::MAX
+    } else {
+        (1u64 << width) - 1
+    };
+    let mut generator = ChaCha20Generator::seed_from_u64(width as u64);
+    let mut values = vec![0, 1, mask, mask >> 1, 1u64 << (width - 1)];
+    values.extend((0..32).map(|_| generator.next_u64() & mask));
+    values
+}
+
+#[test]
+fn int_to_bits_round_trips_msb_first_for_every_width() {
+    for width in 1..=64 {
+        for value in sample_values(width) {
+            let bits = int_to_bits(value, width, true);
+            assert_eq!(bits.len(), width);
+            assert_eq!(bits_to_int(&bits, true), value, "szerokość {}", width);
+        }
+    }
+}
+
+#[test]
+fn int_to_bits_round_trips_lsb_first_for_every_width() {
+    for width in 1..=64 {
+        for value in sample_values(width) {
+            let bits = int_to_bits(value, width, false);
+            assert_eq!(bits.len(), width);
+            assert_eq!(bits_to_int(&bits, false), value, "szerokość {}", width);
+        }
+    }
+}
+
+#[test]
+fn lsb_first_is_reversed_msb_first() {
+    for width in 1..=64 {
+        for value in sample_values(width) {
+            let mut msb = int_to_bits(value, width, true);
+            msb.reverse();
+            assert_eq!(msb, int_to_bits(value, width, false));
+        }
+    }
+}
+
+#[test]
+fn int_to_bits_matches_python_layout() {
+    // _int_to_bits(0b1011, 6, True) == [0, 0, 1, 0, 1, 1]
+    assert_eq!(int_to_bits(0b1011, 6, true), vec![0, 0, 1, 0, 1, 1]);
+    assert_eq!(int_to_bits(0b1011, 6, false), vec![1, 1, 0, 1, 0, 0]);
+    // Szerokość większa niż 64 bity dopełnia starsze pozycje zerami
+    let wide = int_to_bits(u64::MAX, 70, true);
+    assert_eq!(&wide[..6], &[0; 6]);
+    assert!(wide[6..].iter().all(|&bit| bit == 1));
+    assert!(int_to_bits(5, 0, true).is_empty());
+}
+
+#[test]
+fn packed_bits_keep_every_bit() {
+    let mut generator = ChaCha20Generator::seed_from_u64(7);
+    for n in [0, 1, 63, 64, 65, 1000] {
+        let bits: Vec<u8> = (0..n).map(|_| (generator.next_u32() & 1) as u8).collect();
+        let mut packed = PackedBits::try_with_capacity(n).unwrap();
+        packed.extend_from_bits(&bits[..n / 2]);
+        for &bit in &bits[n / 2..] {
+            packed.push(bit);
+        }
+
+        assert_eq!(BitSequence::len(&packed), n);
+        assert!((0..n).all(|i| packed.bit(i) == bits[i]));
+        assert_eq!(&*packed.unpack(0..n), &bits[..]);
+        assert_eq!(&*packed.unpack(n / 3..n / 2), &bits[n / 3..n / 2]);
+        let mut bytes = PackedBits::new();
+        bytes.extend_from_bits(&bits[..n % 8]);
+        for msb_first in [true, false] {
+            let mut from_bytes = bytes.clone();
+            from_bytes.extend_from_bytes(&pack_bits(&bits[n % 8..], msb_first), msb_first);
+            assert_eq!(from_bytes, packed, "{} {}", n, msb_first);
+        }
+        let mut truncated = packed.clone();
+        truncated.truncate(n / 3);
+        truncated.extend_from_bits(&bits[n / 3..]);
+        assert_eq!(truncated, packed);
+        // Fragmenty po 10 bitów, ostatni krótszy
+        let chunks: Vec<Vec<u8>> = bit_chunks(&packed, 10).map(|c| c.into_owned()).collect();
+        assert_eq!(
+            chunks,
+            bits.chunks(10).map(<[u8]>::to_vec).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn packed_bits_report_impossible_allocation() {
+    assert!(PackedBits::try_with_capacity(usize::MAX).is_err());
+    assert_eq!(PackedBits::try_with_capacity(0), Ok(PackedBits::new()));
+}
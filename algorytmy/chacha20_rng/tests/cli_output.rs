@@ -0,0 +1,172 @@
+/*
+Spójność formatów wyjścia binarki chacha20_rng: dla ustalonego seeda bity
+z JSON-a (tablica i base64) spakowane jak w --format raw muszą dać dokładnie
+te same bajty co tryb raw. Na końcu błędne argumenty, w tym --bits ponad
+strumień klucza ChaCha20.
+*/
+
+use chacha20_rng::chacha::KEYSTREAM_LIMIT;
+use chacha20_rng::cli::parse_args;
+use chacha20_rng::output::pack_bits;
+use serde_json::Value;
+use std::process::Command;
+
+fn run(args: &[&str]) -> Vec<u8> {
+    let output = Command::new(env!("CARGO_BIN_EXE_chacha20_rng"))
+        .args(args)
+        .output()
+        .expect("nie udało się uruchomić chacha20_rng");
+    assert!(
+        output.status.success(),
+        "chacha20_rng {:?} zakończył się błędem: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+fn decode_base64(text: &str) -> Vec<u8> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = Vec::new();
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    for c in text.bytes().filter(|&c| c != b'=') {
+        let index = ALPHABET.iter().position(|&a| a == c).unwrap() as u32;
+        acc = (acc << 6) | index;
+        acc_bits += 6;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            out.push((acc >> acc_bits) as u8);
+        }
+    }
+    out
+}
+
+// Zestawy parametrów: seed, liczba bitów, bits_per_value, kolejność
+const CASES: &[(&str, &str, &str, &str)] = &[
+    ("42", "10000", "32", "true"),
+    ("42", "10007", "32", "false"),
+    ("12345", "4099", "12", "true"),
+    ("7", "2048", "64", "false"),
+    ("0", "1537", "128", "true"),
+    ("99", "333", "1", "false"),
+];
+
+#[test]
+fn json_array_and_raw_outputs_are_identical() {
+    for &(seed, n_bits, bpv, msb) in CASES {
+        let msb_first = msb == "true";
+        let json: Value = serde_json::from_slice(&run(&[seed, n_bits, bpv, msb])).unwrap();
+        let bits: Vec<u8> = json["bits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bit| bit.as_u64().unwrap() as u8)
+            .collect();
+        assert_eq!(bits.len(), n_bits.parse::<usize>().unwrap());
+
+        let raw = run(&[seed, n_bits, bpv, msb, "--format", "raw"]);
+        assert_eq!(
+            pack_bits(&bits, msb_first),
+            raw,
+            "seed {} bpv {}",
+            seed,
+            bpv
+        );
+    }
+}
+
+#[test]
+fn json_base64_and_raw_outputs_are_identical() {
+    for &(seed, n_bits, bpv, msb) in CASES {
+        let json: Value =
+            serde_json::from_slice(&run(&[seed, n_bits, bpv, msb, "--json-bits", "base64"]))
+                .unwrap();
+        assert_eq!(json["n_bits"].as_u64(), Some(n_bits.parse().unwrap()));
+
+        let raw = run(&[seed, n_bits, bpv, msb, "--format", "raw"]);
+        assert_eq!(decode_base64(json["bits_base64"].as_str().unwrap()), raw);
+    }
+}
+
+#[test]
+fn json_reports_key_and_nonce_for_fixed_seed() {
+    let json: Value = serde_json::from_slice(&run(&["--seed", "42", "--bits", "8"])).unwrap();
+    assert_eq!(json["seed"].as_u64(), Some(42));
+    assert_eq!(
+        json["key"].as_str(),
+        Some(chacha20_rng::output::to_hex(&chacha20_rng::derive_key(42)).as_str())
+    );
+    assert_eq!(json["nonce"].as_str(), Some("000000000000000000000000"));
+}
+
+#[test]
+fn malformed_arguments_exit_with_error() {
+    for args in [
+        &["abc", "10"][..],
+        &["--bits", "1.5"],
+        &["--bits-per-value", "0"],
+        &["--seed", "1", "--key", "00"],
+        &["--algorithm", "xyz"],
+        &["--seq", "5"],
+        &["--format", "json", "--width", "10", "--bits", "4"],
+        &["--width", "10"],
+        &["--format", "raw", "--width", "8"],
+        &["--format", "hex", "--json-bits", "base64"],
+        &[
+            "--algorithm",
+            "pcg32",
+            "--nonce",
+            "000000000000000000000000",
+        ],
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_chacha20_rng"))
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            !output.status.success(),
+            "{:?} powinno zakończyć się błędem",
+            args
+        );
+        assert!(output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn bits_beyond_chacha20_keystream_are_rejected() {
+    let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+    let limit_bits = KEYSTREAM_LIMIT * 8;
+    let at_limit = limit_bits.to_string();
+    let over_limit = (limit_bits + 1).to_string();
+
+    // bpv = 32: 4 bajty na wartość, limit wypada dokładnie na granicy
+    assert!(parse(&["--bits", &at_limit]).is_ok());
+    let message = parse(&["--bits", &over_limit]).unwrap_err();
+    assert!(message.contains("strumienia klucza"), "{}", message);
+    assert!(parse(&["test", "--bits", &over_limit]).is_err());
+
+    // bpv = 12: truncate zużywa 2 bajty na 12 bitów, contiguous nic nie marnuje
+    assert!(parse(&["--bits", &at_limit, "--bits-per-value", "12"]).is_err());
+    assert!(parse(&[
+        "--bits",
+        &at_limit,
+        "--bits-per-value",
+        "12",
+        "--extraction",
+        "contiguous"
+    ])
+    .is_ok());
+    assert!(parse(&[
+        "--bits",
+        &over_limit,
+        "--bits-per-value",
+        "12",
+        "--extraction",
+        "contiguous"
+    ])
+    .is_err());
+
+    // Inne generatory nie mają tego ograniczenia
+    assert!(parse(&["--algorithm", "pcg32", "--bits", &over_limit]).is_ok());
+}
@@ -0,0 +1,97 @@
+/*
+Wspólne narzędzia testów zgodności z generatorami w Pythonie.
+
+    fixture_lines(text)       -- wiersze danych pliku tests/data/<generator>_python.txt
+    fixture_rows(text)        -- te same wiersze podzielone na pola
+    optional(field)           -- pole liczbowe, "-" = wartość domyślna z Pythona
+    optional_arg(name, value) -- "--name value" albo nic dla wartości domyślnej
+    assert_fixture_hex(bits, hex, context)
+                              -- bity z biblioteki zgadzają się z hex z pliku
+    assert_cli_output(options, bitsPerValue, msbFirst, bits)
+                              -- `rng --format hex` wypisuje te same bity
+    run_rng(args)             -- uruchamia binarkę rng
+
+Pliki z danymi mają jeden przypadek na linię, pola oddzielone spacjami,
+a ostatnie pole to pierwsze n bitów spakowane MSB-first jako hex; linie
+zaczynające się od # to komentarze.
+
+Każdy plik w tests/ jest osobnym crate'em i używa tylko części funkcji.
+*/
+#![allow(dead_code)]
+
+use chacha20_rng::output::{pack_bits, to_hex};
+use std::fmt::Display;
+use std::process::{Command, Output};
+use std::str::FromStr;
+
+pub fn fixture_lines(text: &'static str) -> impl Iterator<Item = &'static str> {
+    text.lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+}
+
+pub fn fixture_rows(text: &'static str) -> impl Iterator<Item = Vec<&'static str>> {
+    fixture_lines(text).map(|line| line.split_whitespace().collect())
+}
+
+pub fn optional<T: FromStr>(field: &str) -> Option<T> {
+    match field {
+        "-" => None,
+        _ => Some(
+            field
+                .parse()
+                .unwrap_or_else(|_| panic!("niepoprawne pole: {}", field)),
+        ),
+    }
+}
+
+pub fn optional_arg(name: &str, value: Option<impl Display>) -> String {
+    value
+        .map(|value| format!("{} {}", name, value))
+        .unwrap_or_default()
+}
+
+pub fn assert_fixture_hex(bits: &[u8], hex: &str, context: &str) {
+    assert_eq!(to_hex(&pack_bits(bits, true)), hex, "{}", context);
+}
+
+pub fn run_rng(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/*
+`rng <options> --bits n [--bits-per-value bpv] [--lsb-first] --format hex
+--width 0` musi wypisać `bits` spakowane jak w --format raw (bity LSB-first
+od najmłodszego bitu bajtu). `options` to opcje generatora oddzielone spacjami.
+*/
+pub fn assert_cli_output(
+    options: &str,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    bits: &[u8],
+) {
+    let command = format!(
+        "{} --bits {} {} {} --format hex --width 0",
+        options,
+        bits.len(),
+        optional_arg("--bits-per-value", bits_per_value),
+        if msb_first { "" } else { "--lsb-first" }
+    );
+    let args: Vec<&str> = command.split_whitespace().collect();
+
+    let output = run_rng(&args);
+    assert!(
+        output.status.success(),
+        "{:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        to_hex(&pack_bits(bits, msb_first)),
+        "{:?}",
+        args
+    );
+}
@@ -0,0 +1,210 @@
+/*
+FFT (fft.rs) porównywana z DFT liczoną wprost dla długości pokrywających
+wszystkie ścieżki: potęgi dwójki (motylki 2 i 4), małe czynniki (motylek
+liczony wprost), duże czynniki pierwsze i duży czynnik środkowy (Bluestein).
+real_spectrum_magnitudes dla n parzystych i nieparzystych w każdym planie
+(SpectrumPlan: Whole, Cosets, Chirp wymuszane małym budżetem) i pamięć planów
+przy budżecie nist::DFT_MEMORY aż do 2^30 próbek.
+*/
+
+use chacha20_rng::fft::{real_spectrum_magnitudes, Complex, Fft, SpectrumPlan};
+use chacha20_rng::nist::DFT_MEMORY;
+use chacha20_rng::xoshiro256_bit_stream;
+use std::f64::consts::PI;
+
+fn naive_dft(input: &[Complex]) -> Vec<Complex> {
+    let n = input.len();
+    (0..n)
+        .map(|k| {
+            input
+                .iter()
+                .enumerate()
+                .fold(Complex::ZERO, |sum, (j, &x)| {
+                    let (sin, cos) = (-2.0 * PI * ((j * k) % n) as f64 / n as f64).sin_cos();
+                    sum + x * Complex::new(cos, sin)
+                })
+        })
+        .collect()
+}
+
+// Deterministyczne wartości z [-1, 1)
+fn signal(n: usize, seed: u64) -> Vec<f64> {
+    xoshiro256_bit_stream(n * 16, None, true, seed)
+        .unwrap()
+        .0
+        .chunks_exact(16)
+        .map(|bits| {
+            let value = bits.iter().fold(0u32, |acc, &bit| acc << 1 | bit as u32);
+            value as f64 / 32768.0 - 1.0
+        })
+        .collect()
+}
+
+#[test]
+fn fft_matches_direct_dft() {
+    let lengths = (0..=40).chain([
+        64,
+        97,
+        100,
+        243,
+        256,
+        500,
+        770,
+        873,
+        1009,
+        1024,
+        2 * 1009,
+        67 * 67,
+    ]);
+    for n in lengths {
+        let values = signal(2 * n, n as u64);
+        let input: Vec<Complex> = values
+            .chunks_exact(2)
+            .map(|pair| Complex::new(pair[0], pair[1]))
+            .collect();
+        let expected = naive_dft(&input);
+
+        let mut buffer = input.clone();
+        Fft::new(n).process(&mut buffer);
+        let tolerance = 1e-9 * (n.max(1) as f64).sqrt();
+        for (k, (x, e)) in buffer.iter().zip(&expected).enumerate() {
+            assert!((*x - *e).norm() <= tolerance, "n = {}, k = {}", n, k);
+        }
+    }
+}
+
+// |X_k| z real_spectrum_magnitudes ułożone według k; każde k < n/2 dokładnie raz
+fn spectrum(values: &[f64], budget: u64) -> Vec<f64> {
+    let n = values.len();
+    let mut magnitudes = vec![None; n / 2];
+    real_spectrum_magnitudes(
+        n,
+        budget,
+        |j| values[j],
+        |k, magnitude| {
+            assert!(magnitudes[k].is_none(), "n = {}, k = {} dwa razy", n, k);
+            magnitudes[k] = Some(magnitude);
+        },
+    );
+    magnitudes
+        .into_iter()
+        .enumerate()
+        .map(|(k, magnitude)| magnitude.unwrap_or_else(|| panic!("n = {}, brak k = {}", n, k)))
+        .collect()
+}
+
+fn direct_magnitudes(values: &[f64]) -> Vec<f64> {
+    let input: Vec<Complex> = values.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    naive_dft(&input)[..values.len() / 2]
+        .iter()
+        .map(|x| x.norm())
+        .collect()
+}
+
+fn assert_spectrum(values: &[f64], expected: &[f64], budget: u64) {
+    for (k, (magnitude, e)) in spectrum(values, budget)
+        .into_iter()
+        .zip(expected)
+        .enumerate()
+    {
+        assert!(
+            (magnitude - e).abs() <= 1e-9,
+            "n = {}, budżet = {}, k = {}",
+            values.len(),
+            budget,
+            k
+        );
+    }
+}
+
+#[test]
+fn real_spectrum_matches_direct_dft() {
+    for n in [0, 1, 2, 3, 7, 8, 100, 101, 127, 998, 1000, 4098] {
+        assert_eq!(SpectrumPlan::new(n, u64::MAX), SpectrumPlan::Whole);
+        let values = signal(n, 7);
+        assert_spectrum(&values, &direct_magnitudes(&values), u64::MAX);
+    }
+}
+
+#[test]
+fn every_plan_matches_direct_dft() {
+    // Budżet jako ułamek pamięci planu Whole: parzyste i nieparzyste c w Cosets,
+    // n pierwsze, 2 * pierwsze i 2 * 3 * 683 w Chirp, także z kilkoma przedziałami k
+    let cases = [
+        (1024, 2, SpectrumPlan::Cosets(8)),
+        (1000, 4, SpectrumPlan::Cosets(40)),
+        (1155, 4, SpectrumPlan::Cosets(5)),
+        (101, 4, SpectrumPlan::Cosets(101)),
+        (
+            998,
+            4,
+            SpectrumPlan::Chirp {
+                cosets: 24,
+                len: 64,
+                ranges: 1,
+            },
+        ),
+        (
+            1009,
+            4,
+            SpectrumPlan::Chirp {
+                cosets: 6,
+                len: 256,
+                ranges: 1,
+            },
+        ),
+        (
+            2 * 1009,
+            4,
+            SpectrumPlan::Chirp {
+                cosets: 12,
+                len: 256,
+                ranges: 1,
+            },
+        ),
+        (
+            4098,
+            16,
+            SpectrumPlan::Chirp {
+                cosets: 97,
+                len: 64,
+                ranges: 3,
+            },
+        ),
+    ];
+    for (n, fraction, expected) in cases {
+        let budget = SpectrumPlan::Whole.memory(n) / fraction;
+        let plan = SpectrumPlan::new(n, budget);
+        assert_eq!(plan, expected, "n = {}", n);
+        assert!(plan.memory(n) <= budget, "n = {}", n);
+        let values = signal(n, n as u64);
+        assert_spectrum(&values, &direct_magnitudes(&values), budget);
+    }
+    // Budżet mniejszy niż tablice twiddle nie wymusza planu warstwami
+    assert_eq!(SpectrumPlan::new(100, 0), SpectrumPlan::Whole);
+}
+
+#[test]
+fn plans_at_2_30_samples_fit_the_budget() {
+    assert_eq!(SpectrumPlan::new(1 << 20, DFT_MEMORY), SpectrumPlan::Whole);
+    assert_eq!(
+        SpectrumPlan::new(1 << 30, DFT_MEMORY),
+        SpectrumPlan::Cosets(32)
+    );
+    for n in [1 << 28, 1_000_000_000, (1 << 30) - 1] {
+        let plan = SpectrumPlan::new(n, DFT_MEMORY);
+        assert!(matches!(plan, SpectrumPlan::Cosets(_)), "n = {}", n);
+        assert!(plan.memory(n) <= DFT_MEMORY, "n = {}", n);
+    }
+    // Największa liczba pierwsza poniżej 2^30 i podwojona największa poniżej 2^29:
+    // akumulator n/2 wartości dzielony na przedziały
+    for n in [(1 << 30) - 35, 2 * ((1 << 29) - 3)] {
+        let plan = SpectrumPlan::new(n, DFT_MEMORY);
+        assert!(
+            matches!(plan, SpectrumPlan::Chirp { ranges, .. } if ranges > 1),
+            "n = {}",
+            n
+        );
+        assert!(plan.memory(n) <= DFT_MEMORY, "n = {}", n);
+    }
+}
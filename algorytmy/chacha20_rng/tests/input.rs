@@ -0,0 +1,230 @@
+/*
+Odczyt bitów z plików (input.rs): każdy format wyjścia output.rs czytany z
+powrotem do PackedBits daje te same bity, rozpoznawanie formatu po zawartości, błędy
+niepoprawnych danych oraz podkomenda `rng analyze`, której raport musi być
+taki sam jak z `rng test` dla tych samych bitów.
+*/
+
+use chacha20_rng::bits::{BitSequence, PackedBits};
+use chacha20_rng::input::{self, detect_format, from_base64, from_hex, read_bits, unpack_bits};
+use chacha20_rng::output::{
+    pack_bits, to_ascii01, to_base64, to_hex, to_json, to_json_base64, wrap_lines, Format,
+};
+use chacha20_rng::xoshiro256_bit_stream;
+use serde_json::{json, Map, Value};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn sample_bits(n_bits: usize) -> Vec<u8> {
+    xoshiro256_bit_stream(n_bits, None, true, 2024).unwrap().0
+}
+
+fn packed_bits(bits: &[u8]) -> PackedBits {
+    let mut packed = PackedBits::new();
+    packed.extend_from_bits(bits);
+    packed
+}
+
+fn run_rng(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("chacha20_rng_{}_{}", std::process::id(), name));
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+#[test]
+fn every_output_format_reads_back() {
+    for n_bits in [0, 1, 8, 13, 1000] {
+        let bits = sample_bits(n_bits);
+        for msb_first in [true, false] {
+            let packed = pack_bits(&bits, msb_first);
+            // Formaty bajtowe: dopełnienie ostatniego bajtu zostaje, stąd prefiks
+            for (format, data) in [
+                (Format::Raw, packed.clone()),
+                (Format::Hex, wrap_lines(&to_hex(&packed), 64).into_bytes()),
+                (
+                    Format::Base64,
+                    wrap_lines(&to_base64(&packed), 76).into_bytes(),
+                ),
+            ] {
+                let read = read_bits(&data, format, msb_first).unwrap();
+                assert_eq!(read.len(), packed.len() * 8, "{} {}", format, n_bits);
+                assert_eq!(*read.unpack(0..n_bits), bits[..], "{} {}", format, n_bits);
+                assert!(read.unpack(n_bits..read.len()).iter().all(|&bit| bit == 0));
+            }
+
+            let json = to_json_base64(&bits, msb_first, 0.0, Map::new());
+            // bit_order z pliku wygrywa z kolejnością podaną w wywołaniu
+            assert_eq!(
+                read_bits(json.as_bytes(), Format::Json, !msb_first).unwrap(),
+                packed_bits(&bits)
+            );
+        }
+
+        let ascii = wrap_lines(&to_ascii01(&bits), 80);
+        assert_eq!(
+            read_bits(ascii.as_bytes(), Format::Ascii01, true).unwrap(),
+            packed_bits(&bits)
+        );
+        let json = to_json(&bits, 0.0, Map::new());
+        assert_eq!(
+            read_bits(json.as_bytes(), Format::Json, true).unwrap(),
+            packed_bits(&bits)
+        );
+    }
+}
+
+#[test]
+fn json_variants_and_base64_decoding() {
+    let export = json!({"id": 7, "test_name": "nist_runs", "generated_bits": [1, 0, 1, 1]});
+    assert_eq!(
+        input::from_json(export.to_string().as_bytes(), true).unwrap(),
+        packed_bits(&[1, 0, 1, 1])
+    );
+    assert_eq!(
+        input::from_json(b"[0, 1, 1]", true).unwrap(),
+        packed_bits(&[0, 1, 1])
+    );
+
+    // Wektory testowe RFC 4648
+    for (text, decoded) in [
+        ("", ""),
+        ("Zg==", "f"),
+        ("Zm8=", "fo"),
+        ("Zm9v", "foo"),
+        ("Zm9vYg==", "foob"),
+        ("Zm9vYmE=", "fooba"),
+        ("Zm9vYmFy", "foobar"),
+    ] {
+        assert_eq!(from_base64(text.as_bytes()).unwrap(), decoded.as_bytes());
+    }
+    assert_eq!(from_hex(b"00 ff\n8A").unwrap(), [0x00, 0xff, 0x8a]);
+    assert_eq!(unpack_bits(&[0x80], true), [1, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(unpack_bits(&[0x80], false), [0, 0, 0, 0, 0, 0, 0, 1]);
+
+    for (data, format) in [
+        (&b"abc"[..], Format::Hex),
+        (b"0g", Format::Hex),
+        (b"Zg=", Format::Base64),
+        (b"Zg==Zg==", Format::Base64),
+        (b"Z===", Format::Base64),
+        (b"0102", Format::Ascii01),
+        (b"{\"bits\": [0, 2]}", Format::Json),
+        (b"{\"bits\": \"0101\"}", Format::Json),
+        (b"{\"bits_base64\": \"AA==\", \"n_bits\": 9}", Format::Json),
+        (b"{\"time\": 0.1}", Format::Json),
+        (b"{", Format::Json),
+    ] {
+        assert!(read_bits(data, format, true).is_err(), "{:?}", data);
+    }
+}
+
+#[test]
+fn format_is_detected_from_content() {
+    assert_eq!(detect_format(b"  {\"bits\": [1]}"), Format::Json);
+    assert_eq!(detect_format(b"[1, 0]"), Format::Json);
+    assert_eq!(detect_format(b"0101\n1100\n"), Format::Ascii01);
+    assert_eq!(detect_format(b"00ff8a\n"), Format::Hex);
+    assert_eq!(detect_format(&[0x00, 0xff, 0x10, 0x31]), Format::Raw);
+
+    // Surowe bajty zaczynające się od '[' albo '{' to nie JSON
+    assert_eq!(detect_format(&[b'[', 0x00, 0xff, 0x10]), Format::Raw);
+    assert_eq!(detect_format(b"{\"time\": 0.1}"), Format::Raw);
+    assert_eq!(detect_format(b"[1, 2]"), Format::Raw);
+}
+
+#[test]
+fn analyze_reads_raw_file_starting_with_bracket() {
+    let mut data = vec![b'['];
+    data.extend(pack_bits(&sample_bits(20000), true));
+    let path = temp_file("bracket.bin", &data);
+    let path = path.to_str().unwrap();
+
+    let output = run_rng(&["analyze", path, "--tests", "monobit"]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["format"].as_str(), Some("raw"));
+    assert_eq!(report["n_bits"].as_u64(), Some(data.len() as u64 * 8));
+
+    // Jawny --format wygrywa z rozpoznawaniem
+    assert!(!run_rng(&["analyze", path, "--format", "json"])
+        .status
+        .success());
+}
+
+#[test]
+fn analyze_matches_test_subcommand() {
+    let generated = run_rng(&["--seed", "42", "--bits", "20000", "--format", "raw"]);
+    assert!(generated.status.success());
+    let path = temp_file("analyze.bin", &generated.stdout);
+    let path = path.to_str().unwrap();
+
+    let tests = "monobit,runs,serial,approximate_entropy";
+    let analyzed = run_rng(&["analyze", path, "--tests", tests]);
+    assert!(analyzed.status.success());
+    let analyzed: Value = serde_json::from_slice(&analyzed.stdout).unwrap();
+    assert_eq!(analyzed["format"].as_str(), Some("raw"));
+    assert_eq!(analyzed["bit_order"].as_str(), Some("msb"));
+    assert_eq!(analyzed["input"].as_str(), Some(path));
+
+    let tested = run_rng(&["test", "--seed", "42", "--bits", "20000", "--tests", tests]);
+    let tested: Value = serde_json::from_slice(&tested.stdout).unwrap();
+    for field in ["n_bits", "alpha", "passed", "tests"] {
+        assert_eq!(analyzed[field], tested[field], "{}", field);
+    }
+
+    // --bits testuje tylko początek pliku, stdin jako "-"
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(["analyze", "-", "--bits", "1000", "--tests", "monobit"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::io::Write::write_all(child.stdin.as_mut().unwrap(), &generated.stdout).unwrap();
+    drop(child.stdin.take());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["n_bits"].as_u64(), Some(1000));
+    assert_eq!(report["input"].as_str(), Some("-"));
+}
+
+#[test]
+fn analyze_rejects_bad_input() {
+    let empty = temp_file("empty.txt", b"\n");
+    let ascii = temp_file("short.txt", b"0101");
+    let hex = temp_file("broken.hex", b"abc");
+    for args in [
+        vec!["analyze"],
+        vec!["analyze", "/nonexistent/bits.bin"],
+        vec!["analyze", empty.to_str().unwrap()],
+        vec!["analyze", ascii.to_str().unwrap(), "--bits", "5"],
+        vec!["analyze", hex.to_str().unwrap()],
+        vec!["analyze", ascii.to_str().unwrap(), "--seed", "1"],
+        vec!["analyze", ascii.to_str().unwrap(), "--algorithm", "pcg32"],
+        vec!["analyze", ascii.to_str().unwrap(), ascii.to_str().unwrap()],
+    ] {
+        let output = run_rng(&args);
+        assert!(!output.status.success(), "{:?}", args);
+    }
+
+    // Za krótki ciąg to nie błąd wywołania: testy zgłaszają błąd w raporcie
+    let output = run_rng(&[
+        "analyze",
+        ascii.to_str().unwrap(),
+        "--tests",
+        "monobit,runs,dft",
+    ]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["format"].as_str(), Some("ascii01"));
+    assert!(report.get("bit_order").is_none());
+    assert!(report["tests"][0]["p_value"].is_number());
+    assert!(report["tests"][2]["error"].is_string());
+}
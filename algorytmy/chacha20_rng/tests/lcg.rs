@@ -0,0 +1,189 @@
+/*
+LCG: zgodność z LCG.py (tests/data/lcg_python.txt — pierwsze 10 000 bitów
+z lcg_bit_stream(seed, a, c, m, 10000, bits_per_value=bpv, msb_first=msb)
+dla modułów potęg dwójki, pierwszych, 2^64 i a, c większych od m),
+warunki Hulla–Dobella, rozkład modułu na czynniki pierwsze i odrzucenie
+bits_per_value = 0.
+*/
+
+mod common;
+
+use chacha20_rng::lcg::{self, hull_dobell_warnings};
+use chacha20_rng::primes::{is_prime, prime_factors};
+use chacha20_rng::{lcg_bit_stream, Lcg};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, optional, run_rng};
+use serde_json::Value;
+
+const FIXTURES: &str = include_str!("data/lcg_python.txt");
+
+struct Case {
+    seed: u128,
+    a: u128,
+    c: u128,
+    m: u128,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        lcg_bit_stream(
+            self.n_bits,
+            self.bits_per_value,
+            self.msb_first,
+            self.seed,
+            self.a,
+            self.c,
+            self.m,
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            seed: fields[0].parse().unwrap(),
+            a: fields[1].parse().unwrap(),
+            c: fields[2].parse().unwrap(),
+            m: fields[3].parse().unwrap(),
+            bits_per_value: optional(fields[4]),
+            msb_first: fields[5] == "true",
+            n_bits: fields[6].parse().unwrap(),
+            hex: fields[7],
+        })
+        .collect()
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!(
+                "a {} c {} m {} bpv {:?}",
+                case.a, case.c, case.m, case.bits_per_value
+            ),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    for case in cases() {
+        let options = format!(
+            "--algorithm lcg --seed {} --a {} --c {} --m {}",
+            case.seed, case.a, case.c, case.m
+        );
+        assert_cli_output(&options, case.bits_per_value, case.msb_first, &case.bits());
+    }
+}
+
+#[test]
+fn known_good_parameters_have_no_warnings() {
+    // glibc, Numerical Recipes, MSVC, Knuth MMIX (m = 2^64)
+    for (a, c, m) in [
+        (1103515245, 12345, 1u128 << 31),
+        (1664525, 1013904223, 1 << 32),
+        (214013, 2531011, 1 << 32),
+        (6364136223846793005, 1442695040888963407, 1 << 64),
+    ] {
+        assert!(hull_dobell_warnings(a, c, m).is_empty(), "a {} c {}", a, c);
+    }
+}
+
+#[test]
+fn each_failed_condition_is_reported() {
+    // gcd(c, m) != 1
+    assert_eq!(hull_dobell_warnings(1103515245, 12346, 1 << 31).len(), 1);
+    // a - 1 niepodzielne przez czynnik pierwszy 3 modułu 3 * 2^10
+    let warnings = hull_dobell_warnings(5, 1, 3 << 10);
+    assert!(
+        warnings.iter().any(|warning| warning.contains(": 3 ")),
+        "{:?}",
+        warnings
+    );
+    // 4 | m, ale a - 1 = 2 niepodzielne przez 4
+    assert_eq!(hull_dobell_warnings(3, 1, 1 << 32).len(), 1);
+    // c = 0
+    assert_eq!(hull_dobell_warnings(16807, 0, (1 << 31) - 1).len(), 1);
+}
+
+#[test]
+fn full_period_for_small_modulus_agrees_with_hull_dobell() {
+    let m = 360u128;
+    for a in 0..m {
+        for c in [0, 1, 7, 12] {
+            let mut generator = Lcg::new(0, a, c, m).unwrap();
+            let mut seen = vec![false; m as usize];
+            for _ in 0..m {
+                seen[generator.next_value() as usize] = true;
+            }
+            let full_period = seen.iter().all(|&visited| visited);
+            assert_eq!(
+                full_period,
+                hull_dobell_warnings(a, c, m).is_empty(),
+                "a {} c {}",
+                a,
+                c
+            );
+        }
+    }
+}
+
+#[test]
+fn prime_factors_of_large_moduli() {
+    assert_eq!(prime_factors(1 << 64), vec![2]);
+    assert_eq!(
+        prime_factors(u64::MAX as u128),
+        vec![3, 5, 17, 257, 641, 65537, 6700417]
+    );
+    assert_eq!(prime_factors(600851475143), vec![71, 839, 1471, 6857]);
+    assert_eq!(
+        prime_factors(4294967291 * 4294967279),
+        vec![4294967279, 4294967291]
+    );
+    assert!(is_prime(18446744073709551557));
+    assert!(!is_prime(3215031751));
+}
+
+#[test]
+fn invalid_modulus_is_rejected() {
+    assert!(Lcg::new(1, 1, 1, 0).is_err());
+    assert!(Lcg::new(1, 1, 1, 1).is_err());
+    assert!(Lcg::new(1, 1, 1, (1 << 64) + 1).is_err());
+    assert_eq!(lcg::default_bits_per_value(1 << 31), 32);
+
+    for m in ["0", "2^65", "abc"] {
+        let output = run_rng(&["--algorithm", "lcg", "--m", m]);
+        assert!(!output.status.success(), "--m {}", m);
+    }
+}
+
+#[test]
+fn json_reports_parameters_and_warnings() {
+    let output = run_rng(&["--algorithm", "lcg", "--a", "3", "--c", "2", "--m", "2**32"]);
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["a"].as_u64(), Some(3));
+    assert_eq!(json["m"].as_u64(), Some(1 << 32));
+    assert_eq!(json["full_period"].as_bool(), Some(false));
+    assert_eq!(json["warnings"].as_array().unwrap().len(), 2);
+    assert_eq!(json["bits"].as_array().unwrap().len(), 200);
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    assert!(lcg_bit_stream(100, Some(0), true, 1, 5, 3, 16).is_err());
+    assert!(lcg_bit_stream(0, Some(0), true, 1, 5, 3, 16).is_err());
+
+    let mut generator = Lcg::new(1, 5, 3, 16).unwrap();
+    let mut bits = Vec::new();
+    assert!(generator.extend_bits(&mut bits, 100, 0, true).is_err());
+}
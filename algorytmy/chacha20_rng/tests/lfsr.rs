@@ -0,0 +1,160 @@
+/*
+Złożoność liniowa (lfsr.rs): Berlekamp–Massey na słowach u64 porównany
+z wersją bit po bicie przeniesioną z run_rng_test.py (wynik i profil), ciągi
+o znanej złożoności (LFSR, xorshift32) i podkomenda `rng linear-complexity`.
+*/
+
+use chacha20_rng::bits::int_to_bits;
+use chacha20_rng::lfsr::{self, berlekamp_massey, complexity_profile};
+use chacha20_rng::xoshiro256_bit_stream;
+use serde_json::Value;
+use std::process::Command;
+
+// berlekamp_massey z backendu, z zapisem punktów skoku
+fn reference_profile(bits: &[u8]) -> (usize, Vec<(usize, usize)>) {
+    let n = bits.len();
+    let mut c = vec![0u8; n + 1];
+    let mut b = vec![0u8; n + 1];
+    c[0] = 1;
+    b[0] = 1;
+    let (mut l, mut m) = (0usize, -1i64);
+    let mut jumps = Vec::new();
+    for big_n in 0..n {
+        let mut d = bits[big_n];
+        for i in 1..=l {
+            d ^= c[i] & bits[big_n - i];
+        }
+        if d == 1 {
+            let t = c.clone();
+            let shift = (big_n as i64 - m) as usize;
+            for i in 0..=n - shift {
+                c[shift + i] ^= b[i];
+            }
+            if 2 * l <= big_n {
+                l = big_n + 1 - l;
+                m = big_n as i64;
+                b = t;
+                jumps.push((big_n + 1, l));
+            }
+        }
+    }
+    (l, jumps)
+}
+
+fn run_rng(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn packed_berlekamp_massey_matches_bitwise_reference() {
+    for (n_bits, seed) in [
+        (0, 0),
+        (1, 1),
+        (2, 2),
+        (63, 3),
+        (64, 4),
+        (65, 5),
+        (500, 6),
+        (3000, 7),
+    ] {
+        let bits = xoshiro256_bit_stream(n_bits, None, true, seed).unwrap().0;
+        let (complexity, jumps) = reference_profile(&bits);
+        let profile = complexity_profile(&bits);
+        assert_eq!(profile.linear_complexity, complexity, "n = {}", n_bits);
+        assert_eq!(profile.jumps, jumps, "n = {}", n_bits);
+        assert_eq!(berlekamp_massey(&bits), complexity);
+        for at in [0, n_bits / 3, n_bits] {
+            let (expected, _) = reference_profile(&bits[..at]);
+            assert_eq!(
+                profile.complexity_at(at),
+                expected,
+                "n = {}, N = {}",
+                n_bits,
+                at
+            );
+        }
+    }
+
+    // Same zera: L = 0; jedynka na końcu: L = n
+    assert_eq!(berlekamp_massey(&[0; 200]), 0);
+    let mut impulse = vec![0u8; 200];
+    impulse[199] = 1;
+    assert_eq!(berlekamp_massey(&impulse), 200);
+}
+
+#[test]
+fn linear_sequences_are_predictable() {
+    // LFSR x^31 + x^3 + 1 (prymitywny): złożoność 31 niezależnie od długości
+    let mut state: Vec<u8> = xoshiro256_bit_stream(31, None, true, 9).unwrap().0;
+    state[0] = 1;
+    while state.len() < 5000 {
+        let t = state.len();
+        state.push(state[t - 31] ^ state[t - 28]);
+    }
+    let profile = complexity_profile(&state);
+    assert_eq!(profile.linear_complexity, 31);
+    assert!(profile.predictable());
+
+    // xorshift32: każda pozycja bitu ma L <= 32, cały ciąg L <= 32 * 32
+    let mut x = 2463534242u32;
+    let mut bits = Vec::new();
+    for _ in 0..200 {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        bits.extend(int_to_bits(x as u64, 32, true));
+    }
+    assert!(berlekamp_massey(&bits) <= 1024);
+    let report = lfsr::report(&bits, 32);
+    assert_eq!(report["predictable"], true);
+    assert_eq!(report["passed"], false);
+    for lane in report["lanes"].as_array().unwrap() {
+        assert!(lane["linear_complexity"].as_u64().unwrap() <= 32);
+        assert_eq!(lane["predictable"], true);
+    }
+
+    // Ciąg losowy trzyma się N/2
+    let random = xoshiro256_bit_stream(4000, None, true, 11).unwrap().0;
+    let report = lfsr::report(&random, 1);
+    assert_eq!(report["passed"], true);
+    assert!(report.get("lanes").is_none());
+    let complexity = report["linear_complexity"].as_u64().unwrap();
+    assert!(complexity.abs_diff(2000) <= 20);
+    assert_eq!(
+        report["profile"].as_array().unwrap().len(),
+        lfsr::PROFILE_POINTS
+    );
+}
+
+#[test]
+fn linear_complexity_subcommand_flags_lcg_low_bits() {
+    let output = run_rng(&["linear-complexity", "--algorithm", "lcg", "--bits", "20000"]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["algorithm"].as_str(), Some("lcg"));
+    assert_eq!(report["n_bits"].as_u64(), Some(20000));
+    assert_eq!(report["passed"].as_bool(), Some(false));
+    // m = 2^31, 32 bity na wartość MSB-first: najmłodszy bit x (ostatnia pozycja) ma okres 2
+    let lanes = report["lanes"].as_array().unwrap();
+    assert_eq!(lanes.len(), 32);
+    assert_eq!(lanes[31]["linear_complexity"].as_u64(), Some(2));
+    assert_eq!(lanes[31]["predictable"].as_bool(), Some(true));
+
+    let output = run_rng(&["linear-complexity", "--seed", "3", "--bits", "20000"]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["algorithm"].as_str(), Some("chacha20"));
+    assert_eq!(report["passed"].as_bool(), Some(true));
+
+    for args in [
+        vec!["linear-complexity", "--format", "hex"],
+        vec!["linear-complexity", "--tests", "monobit"],
+        vec!["linear-complexity", "--alpha", "0.05"],
+        vec!["--bits", "100", "linear-complexity"],
+    ] {
+        assert!(!run_rng(&args).status.success(), "{:?}", args);
+    }
+}
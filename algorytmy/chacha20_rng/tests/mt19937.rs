@@ -0,0 +1,266 @@
+/*
+Mersenne Twister: wektory implementacji referencyjnych (tests/data/mt19937ar.out
+dla init_by_array({0x123, 0x234, 0x345, 0x456}) i tests/data/mt19937-64.out dla
+init_by_array64({0x12345, 0x23456, 0x34567, 0x45678}) — 1000 liczb całkowitych,
+potem 1000 wartości genrand_real2), 10 000. wartość std::mt19937 i
+std::mt19937_64 oraz zgodność z PythonRNG.py (tests/data/mt19937_python.txt —
+python_random_bit_stream(seed, 10000, bits_per_value=bpv, msb_first=msb), także
+dla seedów dłuższych niż 32 bity i bits_per_value > 32). bits_per_value = 0
+jest błędem, a nie paniką.
+*/
+
+mod common;
+
+use chacha20_rng::bits::bits_to_int;
+use chacha20_rng::mt19937::{self, python_key};
+use chacha20_rng::output::{pack_bits, to_hex};
+use chacha20_rng::{mt19937_64_bit_stream, mt19937_bit_stream, Mt19937, Mt19937_64, MtInit};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, optional, run_rng};
+use rand_core::SeedableRng;
+use serde_json::Value;
+
+const MT19937AR_OUT: &str = include_str!("data/mt19937ar.out");
+const MT19937_64_OUT: &str = include_str!("data/mt19937-64.out");
+const FIXTURES: &str = include_str!("data/mt19937_python.txt");
+
+struct Case {
+    seed: u64,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        mt19937_bit_stream(
+            self.n_bits,
+            self.bits_per_value,
+            self.msb_first,
+            self.seed,
+            MtInit::InitByArray,
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            seed: fields[0].parse().unwrap(),
+            bits_per_value: optional(fields[1]),
+            msb_first: fields[2] == "true",
+            n_bits: fields[3].parse().unwrap(),
+            hex: fields[4],
+        })
+        .collect()
+}
+
+// Dwie sekcje pliku .out: liczby całkowite i wartości rzeczywiste (%10.8f)
+fn reference_sections(text: &str) -> (Vec<u64>, Vec<f64>) {
+    let (integers, reals) = text.split_once("\n\n").unwrap();
+    let numbers = |section: &str| -> Vec<String> {
+        section
+            .lines()
+            .skip(1)
+            .flat_map(|line| line.split_whitespace().map(str::to_string))
+            .collect()
+    };
+    (
+        numbers(integers)
+            .iter()
+            .map(|n| n.parse().unwrap())
+            .collect(),
+        numbers(reals).iter().map(|n| n.parse().unwrap()).collect(),
+    )
+}
+
+#[test]
+fn mt19937ar_reference_output() {
+    let (integers, reals) = reference_sections(MT19937AR_OUT);
+    assert_eq!((integers.len(), reals.len()), (1000, 1000));
+
+    let mut generator = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+    for (index, &expected) in integers.iter().enumerate() {
+        assert_eq!(
+            generator.next_u32() as u64,
+            expected,
+            "genrand_int32 #{}",
+            index
+        );
+    }
+    // genrand_real2 = genrand_int32 / 2^32, wypisane z 8 cyframi po przecinku
+    for (index, &expected) in reals.iter().enumerate() {
+        let value = generator.next_u32() as f64 / 4294967296.0;
+        assert!((value - expected).abs() <= 5e-9, "genrand_real2 #{}", index);
+    }
+}
+
+#[test]
+fn mt19937_64_reference_output() {
+    let (integers, reals) = reference_sections(MT19937_64_OUT);
+    assert_eq!((integers.len(), reals.len()), (1000, 1000));
+
+    let mut generator = Mt19937_64::from_key(&[0x12345, 0x23456, 0x34567, 0x45678]);
+    for (index, &expected) in integers.iter().enumerate() {
+        assert_eq!(generator.next_u64(), expected, "genrand64_int64 #{}", index);
+    }
+    // genrand64_real2 = (genrand64_int64 >> 11) / 2^53
+    for (index, &expected) in reals.iter().enumerate() {
+        let value = (generator.next_u64() >> 11) as f64 / 9007199254740992.0;
+        assert!(
+            (value - expected).abs() <= 5e-9,
+            "genrand64_real2 #{}",
+            index
+        );
+    }
+}
+
+#[test]
+fn init_genrand_matches_cpp_standard_library() {
+    // Wymaganie standardu C++: 10 000. wywołanie domyślnie skonstruowanego generatora
+    let mut generator = Mt19937::new(5489);
+    let value = (0..10000).map(|_| generator.next_u32()).last();
+    assert_eq!(value, Some(4123659995));
+
+    let mut generator = Mt19937_64::new(5489);
+    let value = (0..10000).map(|_| generator.next_u64()).last();
+    assert_eq!(value, Some(9981545732273789042));
+
+    assert_eq!(Mt19937_64::seed_from_u64(5489), Mt19937_64::new(5489));
+    assert_eq!(
+        Mt19937::from_seed(5489u32.to_le_bytes()),
+        Mt19937::new(5489)
+    );
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!("seed {} bpv {:?}", case.seed, case.bits_per_value),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    for case in cases() {
+        let options = format!("--algorithm python --seed {}", case.seed);
+        assert_cli_output(&options, case.bits_per_value, case.msb_first, &case.bits());
+    }
+}
+
+#[test]
+fn wide_values_join_words_like_getrandbits() {
+    assert_eq!(python_key(0), [0]);
+    assert_eq!(python_key(u32::MAX as u64), [u32::MAX]);
+    assert_eq!(python_key((7 << 32) | 5), [5, 7]);
+    assert_eq!(Mt19937::seed_from_u64(42), Mt19937::from_key(&[42]));
+
+    // getrandbits(48): pierwsze słowo to młodsze 32 bity, z drugiego 16 najstarszych
+    let mut words = Mt19937::from_python_seed(1);
+    let (low, high) = (words.next_u32() as u64, words.next_u32() as u64);
+    let mut generator = Mt19937::from_python_seed(1);
+    let mut bits = Vec::new();
+    generator.next_bits(48, true, &mut bits);
+    assert_eq!(bits_to_int(&bits, true), ((high >> 16) << 32) | low);
+
+    // To samo dla MT19937-64: 100 bitów to słowo 64-bitowe i 36 bitów drugiego
+    let mut words = Mt19937_64::new(3);
+    let (low, high) = (words.next_u64(), words.next_u64());
+    let (bits, _) = mt19937_64_bit_stream(100, Some(100), true, 3, MtInit::InitGenrand).unwrap();
+    assert_eq!(bits_to_int(&bits[..36], true), high >> 28);
+    assert_eq!(bits_to_int(&bits[36..], true), low);
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    // init_genrand dla MT19937 przyjmuje tylko 32-bitowy seed
+    assert!(mt19937_bit_stream(10, None, true, 1 << 32, MtInit::InitGenrand).is_err());
+    assert!(Mt19937::seeded(u32::MAX as u64, MtInit::InitGenrand).is_ok());
+
+    for args in [
+        [
+            "--algorithm",
+            "mt19937",
+            "--init",
+            "init_genrand",
+            "--seed",
+            "4294967296",
+        ],
+        ["--algorithm", "mt19937", "--init", "xyz", "--seed", "1"],
+        [
+            "--algorithm",
+            "pcg32",
+            "--init",
+            "init_genrand",
+            "--seed",
+            "1",
+        ],
+    ] {
+        assert!(!run_rng(&args).status.success(), "{:?}", args);
+    }
+    assert!(run_rng(&[
+        "--algorithm",
+        "mt19937-64",
+        "--init",
+        "genrand",
+        "--seed",
+        "4294967296"
+    ])
+    .status
+    .success());
+}
+
+#[test]
+fn json_reports_initialization() {
+    let output = run_rng(&["--algorithm", "mt19937", "--seed", "4294967301"]);
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["algorithm"].as_str(), Some("mt19937"));
+    assert_eq!(json["init"].as_str(), Some("init_by_array"));
+    assert_eq!(json["key"], serde_json::json!([5, 1]));
+    assert_eq!(json["bits"].as_array().unwrap().len(), 200);
+
+    let output = run_rng(&["--algorithm", "mt64", "--bits", "64", "--format", "hex"]);
+    let (bits, _) = mt19937_64_bit_stream(
+        64,
+        None,
+        true,
+        mt19937::DEFAULT_SEED,
+        mt19937::DEFAULT_INIT_64,
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        to_hex(&pack_bits(&bits, true))
+    );
+
+    let output = run_rng(&["--algorithm", "mt19937-64"]);
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["init"].as_str(), Some("init_genrand"));
+    assert_eq!(json["initial_state"].as_u64(), Some(5489));
+    assert!(json.get("key").is_none());
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    let init = MtInit::InitGenrand;
+    assert!(mt19937_bit_stream(100, Some(0), true, 5489, init).is_err());
+    assert!(mt19937_64_bit_stream(100, Some(0), true, 5489, init).is_err());
+
+    let mut bits = Vec::new();
+    assert!(Mt19937::new(5489)
+        .extend_bits(&mut bits, 100, 0, true)
+        .is_err());
+    assert!(Mt19937_64::new(5489)
+        .extend_bits(&mut bits, 100, 0, true)
+        .is_err());
+}
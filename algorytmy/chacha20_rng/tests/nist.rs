@@ -0,0 +1,365 @@
+/*
+Testy NIST SP 800-22: zgodność z RunRNGTestUseCase._nist_*_test
+(tests/data/nist_python.txt — dla każdego testu i źródła surowe p-wartości
+i słownik {passed, score, statistics} z Pythona). P-wartości muszą zgadzać się
+do 1e-9, a wynik JSON kluczami, typami liczb (int / float) i wartościami.
+Test DFT (przez FFT) musi też odtworzyć p-wartości z dodatku B SP 800-22
+dla pierwszych 10^6 bitów rozwinięć e, pi i sqrt(2)
+(tests/data/sp800_22_*.bin, bity MSB first, razem z częścią całkowitą — jak
+data.e, data.pi i data.sqrt2 z pakietu NIST STS).
+Podkomenda `rng test` musi dawać te same p-wartości co biblioteka.
+*/
+
+mod common;
+
+use chacha20_rng::bits::{BitSequence, PackedBits};
+use chacha20_rng::input::unpack_bits;
+use chacha20_rng::nist::{self, NistTest, TestResult};
+use chacha20_rng::{lcg_bit_stream, mt19937_bit_stream, MtInit};
+use common::{fixture_lines, run_rng};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+const FIXTURES: &str = include_str!("data/nist_python.txt");
+
+const TOLERANCE: f64 = 1e-9;
+
+struct Case {
+    test: NistTest,
+    source: &'static str,
+    p_values: Vec<f64>,
+    result: Value,
+}
+
+fn cases() -> Vec<Case> {
+    // Ostatnie pole to JSON ze spacjami, więc tylko trzy pierwsze są dzielone
+    fixture_lines(FIXTURES)
+        .map(|line| {
+            let fields: Vec<&'static str> = line.splitn(4, ' ').collect();
+            Case {
+                test: fields[0].parse().unwrap(),
+                source: fields[1],
+                p_values: match fields[2] {
+                    "-" => Vec::new(),
+                    values => values.split(',').map(|p| p.parse().unwrap()).collect(),
+                },
+                result: serde_json::from_str(fields[3]).unwrap(),
+            }
+        })
+        .collect()
+}
+
+// mt:<seed>:<n> albo lcg16:<seed>:<n>, jak w nagłówku pliku z danymi
+fn source_bits(source: &str) -> Vec<u8> {
+    let fields: Vec<&str> = source.split(':').collect();
+    let n_bits: usize = fields[2].parse().unwrap();
+    let (bits, _) = match fields[0] {
+        "mt" => mt19937_bit_stream(
+            n_bits,
+            None,
+            true,
+            fields[1].parse().unwrap(),
+            MtInit::InitByArray,
+        ),
+        "lcg16" => lcg_bit_stream(
+            n_bits,
+            Some(16),
+            true,
+            fields[1].parse().unwrap(),
+            25173,
+            13849,
+            65536,
+        ),
+        other => panic!("nieznane źródło {}", other),
+    }
+    .unwrap();
+    bits
+}
+
+// Porównanie JSON: te same klucze, liczby całkowite dokładnie, float do TOLERANCE
+fn assert_json_close(actual: &Value, expected: &Value, path: &str) {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(e)) => {
+            assert_eq!(a.is_f64(), e.is_f64(), "{}: {} vs {}", path, a, e);
+            if e.is_f64() {
+                let (a, e) = (a.as_f64().unwrap(), e.as_f64().unwrap());
+                assert!((a - e).abs() <= TOLERANCE, "{}: {} vs {}", path, a, e);
+            } else {
+                assert_eq!(a, e, "{}", path);
+            }
+        }
+        (Value::Object(a), Value::Object(e)) => {
+            let mut keys: Vec<&String> = a.keys().collect();
+            let mut expected_keys: Vec<&String> = e.keys().collect();
+            keys.sort();
+            expected_keys.sort();
+            assert_eq!(keys, expected_keys, "{}", path);
+            for (key, value) in e {
+                assert_json_close(&a[key], value, &format!("{}.{}", path, key));
+            }
+        }
+        (Value::Array(a), Value::Array(e)) => {
+            assert_eq!(a.len(), e.len(), "{}", path);
+            for (index, (a, e)) in a.iter().zip(e).enumerate() {
+                assert_json_close(a, e, &format!("{}[{}]", path, index));
+            }
+        }
+        _ => assert_eq!(actual, expected, "{}", path),
+    }
+}
+
+#[test]
+fn results_match_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    let mut sources: HashMap<&str, Vec<u8>> = HashMap::new();
+    for case in &cases {
+        let bits = sources
+            .entry(case.source)
+            .or_insert_with(|| source_bits(case.source));
+        let context = format!("{} {}", case.test, case.source);
+
+        let result = case.test.run(bits);
+        assert_eq!(result.p_values.len(), case.p_values.len(), "{}", context);
+        for (p, expected) in result.p_values.iter().zip(&case.p_values) {
+            assert!(
+                (p - expected).abs() <= TOLERANCE,
+                "{}: {} vs {}",
+                context,
+                p,
+                expected
+            );
+        }
+        assert_json_close(&result.to_json(), &case.result, &context);
+    }
+
+    // Każdy z 15 testów ma w danych co najmniej jeden wynik z p-wartością
+    for &test in NistTest::ALL {
+        assert!(
+            cases
+                .iter()
+                .any(|case| case.test == test && !case.p_values.is_empty()),
+            "{}",
+            test
+        );
+    }
+}
+
+#[test]
+fn packed_bits_give_the_same_results() {
+    // rng test trzyma ciąg w PackedBits — wynik musi być ten sam co dla Vec<u8>
+    for source in ["mt:7:120000", "lcg16:3:20000"] {
+        let bits = source_bits(source);
+        let mut packed = PackedBits::with_capacity(bits.len());
+        packed.extend_from_bits(&bits);
+        for &test in NistTest::ALL {
+            let (expected, result) = (test.run(&bits), test.run(&packed));
+            assert_eq!(result.p_values, expected.p_values, "{} {}", test, source);
+            assert_eq!(result.to_json(), expected.to_json(), "{} {}", test, source);
+        }
+    }
+}
+
+// Ciąg 0101... dowolnej długości bez trzymania bitów w pamięci
+struct Alternating(usize);
+
+impl BitSequence for Alternating {
+    fn len(&self) -> usize {
+        self.0
+    }
+
+    fn bit(&self, index: usize) -> u8 {
+        (index % 2) as u8
+    }
+
+    fn unpack(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        Cow::Owned(range.map(|index| self.bit(index)).collect())
+    }
+}
+
+#[test]
+fn dft_rejects_sequences_over_2_30_bits() {
+    let result = nist::dft(&Alternating(nist::DFT_MAX_BITS + 1));
+    assert!(!result.passed);
+    assert_eq!(
+        result.statistics["error"].as_str(),
+        Some("Maximum 2^30 bits supported")
+    );
+    assert_eq!(nist::DFT_MAX_BITS, 1 << 30);
+}
+
+#[test]
+fn dft_reproduces_sp800_22_reference_values() {
+    // Dodatek B: p-wartości testu widmowego dla 10^6 bitów rozwinięć
+    for (name, data, expected) in [
+        ("pi", &include_bytes!("data/sp800_22_pi.bin")[..], 0.010186),
+        ("e", include_bytes!("data/sp800_22_e.bin"), 0.847187),
+        ("sqrt2", include_bytes!("data/sp800_22_sqrt2.bin"), 0.581909),
+    ] {
+        let bits = unpack_bits(data, true);
+        assert_eq!(bits.len(), 1_000_000);
+        let result = nist::dft(&bits);
+        assert_eq!(
+            nist::round_python(result.p_values[0], 6),
+            expected,
+            "{}",
+            name
+        );
+        assert!(result.passed, "{}", name);
+    }
+}
+
+#[test]
+fn names_follow_backend_test_names() {
+    assert_eq!(NistTest::ALL.len(), 15);
+    for &test in NistTest::ALL {
+        assert_eq!(test.name().parse::<NistTest>(), Ok(test));
+        assert!(test.name().starts_with("nist_"));
+    }
+    assert_eq!("monobit".parse(), Ok(NistTest::Monobit));
+    assert_eq!(
+        "random-excursions-variant".parse(),
+        Ok(NistTest::RandomExcursionsVariant)
+    );
+    assert_eq!("NIST_DFT".parse(), Ok(NistTest::Dft));
+    assert!("frequency".parse::<NistTest>().is_err());
+}
+
+#[test]
+fn empty_input_is_an_error_not_a_panic() {
+    // Python dzieli tu przez zero; wynik ma kształt błędu z backendu
+    for &test in NistTest::ALL {
+        let result: TestResult = test.run(&[]);
+        assert!(!result.passed, "{}", test);
+        assert_eq!(result.score, 0.0, "{}", test);
+        assert!(result.p_values.is_empty(), "{}", test);
+        assert!(result.statistics.contains_key("error"), "{}", test);
+    }
+
+    // Stały ciąg: pre-test runs odrzuca go, monobit daje p bliskie 0
+    let ones = vec![1u8; 1000];
+    assert!(nist::runs(&ones).statistics.contains_key("error"));
+    assert!(!nist::monobit(&ones).passed);
+}
+
+#[test]
+fn round_python_rounds_half_to_even_on_binary_value() {
+    assert_eq!(nist::round_python(0.5, 0), 0.0);
+    assert_eq!(nist::round_python(2.675, 2), 2.67);
+    assert_eq!(nist::round_python(0.0001378537545612421, 4), 0.0001);
+    assert_eq!(nist::round_python(2.102674473180674, 6), 2.102674);
+}
+
+#[test]
+fn test_subcommand_reports_library_results() {
+    let output = run_rng(&[
+        "test",
+        "--algorithm",
+        "mt19937",
+        "--seed",
+        "42",
+        "--bits",
+        "20000",
+        "--tests",
+        "monobit,nist_runs,serial,universal",
+    ]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["algorithm"].as_str(), Some("mt19937"));
+    assert_eq!(report["n_bits"].as_u64(), Some(20000));
+    assert_eq!(report["alpha"].as_f64(), Some(nist::THRESHOLD));
+
+    let (bits, _) = mt19937_bit_stream(20000, None, true, 42, MtInit::InitByArray).unwrap();
+    let entries = report["tests"].as_array().unwrap();
+    let tests = [
+        NistTest::Monobit,
+        NistTest::Runs,
+        NistTest::Serial,
+        NistTest::Universal,
+    ];
+    assert_eq!(entries.len(), tests.len());
+    for (entry, test) in entries.iter().zip(tests) {
+        let result = test.run(&bits);
+        assert_eq!(entry["test"].as_str(), Some(test.name()));
+        assert_eq!(entry["details"], result.to_json()["statistics"]);
+        match result.p_values.iter().copied().reduce(f64::min) {
+            Some(p_value) => {
+                assert_eq!(entry["p_value"].as_f64(), Some(p_value), "{}", test);
+                assert_eq!(entry["passed"].as_bool(), Some(result.passed), "{}", test);
+            }
+            // Za krótki ciąg dla universal: błąd zamiast p-wartości
+            None => {
+                assert!(entry["p_value"].is_null());
+                assert!(entry["error"].is_string());
+            }
+        }
+    }
+    assert_eq!(entries[2]["p_values"].as_array().unwrap().len(), 2);
+    assert_eq!(report["passed"].as_bool(), Some(false));
+}
+
+#[test]
+fn alpha_decides_pass_or_fail() {
+    let report = |alpha: &str| -> Value {
+        let output = run_rng(&[
+            "test", "--seed", "7", "--bits", "5000", "--tests", "monobit", "--alpha", alpha,
+        ]);
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let p_value = report("0.01")["tests"][0]["p_value"].as_f64().unwrap();
+    assert_eq!(
+        report("0.01")["tests"][0]["passed"].as_bool(),
+        Some(p_value >= 0.01)
+    );
+    // Próg tuż nad p-wartością oblewa test, tuż pod nią go przepuszcza
+    let above = report(&(p_value * 1.0001).min(0.999999).to_string());
+    assert_eq!(above["passed"].as_bool(), Some(false));
+    let below = report(&(p_value * 0.9999).to_string());
+    assert_eq!(below["tests"][0]["passed"].as_bool(), Some(true));
+    assert_eq!(below["passed"].as_bool(), Some(true));
+}
+
+#[test]
+fn test_subcommand_defaults_and_invalid_options() {
+    let output = run_rng(&["test", "--algorithm", "pcg32", "--bits", "2000"]);
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = report["tests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["test"].as_str().unwrap())
+        .collect();
+    // Domyślnie wszystkie testy w kolejności backendu
+    let all: Vec<&str> = NistTest::ALL.iter().map(|test| test.name()).collect();
+    assert_eq!(names, all);
+
+    for args in [
+        vec!["test", "--format", "hex"],
+        vec!["test", "--json-bits", "base64"],
+        vec!["test", "--tests", "frequency"],
+        vec!["test", "--tests", ","],
+        vec!["test", "--alpha", "0"],
+        vec!["test", "--alpha", "1.5"],
+        vec!["--tests", "monobit"],
+        vec!["--alpha", "0.05"],
+        vec!["--seed", "1", "test"],
+    ] {
+        assert!(!run_rng(&args).status.success(), "{:?}", args);
+    }
+}
+
+#[test]
+fn bits_over_available_memory_are_an_error_not_an_abort() {
+    let max = u64::MAX.to_string();
+    for command in ["test", "linear-complexity"] {
+        let output = run_rng(&[command, "--algorithm", "pcg32", "--bits", &max]);
+        assert_eq!(output.status.code(), Some(1), "{}", command);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("nie mieści się w pamięci"), "{}", stderr);
+    }
+}
@@ -0,0 +1,146 @@
+/*
+Park–Miller: test 10 000. wartości z pracy Parka i Millera (1988), zgodność
+metody Schrage'a z bezpośrednim a * x mod m, odrzucanie seedów będących
+punktem stałym oraz zgodność z Park_Miller.py (tests/data/park_miller_python.txt
+— pierwsze 10 000 bitów z park_miller_bit_stream(seed, 10000, bits_per_value=bpv,
+msb_first=msb)). bits_per_value = 0 jest błędem, a nie paniką.
+*/
+
+mod common;
+
+use chacha20_rng::park_miller::{MODULUS, MULTIPLIERS};
+use chacha20_rng::{park_miller_bit_stream, ParkMiller};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, optional, run_rng};
+use serde_json::Value;
+
+const FIXTURES: &str = include_str!("data/park_miller_python.txt");
+
+struct Case {
+    seed: u64,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        park_miller_bit_stream(
+            self.n_bits,
+            self.bits_per_value,
+            self.msb_first,
+            self.seed,
+            16807,
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            seed: fields[0].parse().unwrap(),
+            bits_per_value: optional(fields[1]),
+            msb_first: fields[2] == "true",
+            n_bits: fields[3].parse().unwrap(),
+            hex: fields[4],
+        })
+        .collect()
+}
+
+fn value_10000(a: u64) -> u64 {
+    let mut generator = ParkMiller::new(1, a).unwrap();
+    let mut value = 0;
+    for _ in 0..10000 {
+        value = generator.next_value();
+    }
+    value
+}
+
+#[test]
+fn ten_thousandth_value_from_seed_1() {
+    // Park & Miller (1988) dla a = 16807; 48271 jak std::minstd_rand w C++11
+    assert_eq!(value_10000(16807), 1043618065);
+    assert_eq!(value_10000(48271), 399268537);
+}
+
+#[test]
+fn schrage_matches_direct_multiplication() {
+    for a in MULTIPLIERS {
+        for seed in [1, 2, 12345, 123456789, MODULUS - 1] {
+            let mut generator = ParkMiller::new(seed, a).unwrap();
+            let mut x = seed;
+            for _ in 0..100_000 {
+                x = a * x % MODULUS;
+                assert_eq!(generator.next_value(), x, "a {} seed {}", a, seed);
+            }
+        }
+    }
+    assert_eq!(value_10000(69621), 190055451);
+}
+
+#[test]
+fn fixed_point_seeds_are_rejected() {
+    for seed in [0, MODULUS, 2 * MODULUS] {
+        assert!(ParkMiller::new(seed, 16807).is_err(), "seed {}", seed);
+        assert!(park_miller_bit_stream(100, None, true, seed, 16807).is_err());
+
+        let output = run_rng(&["--algorithm", "park-miller", "--seed", &seed.to_string()]);
+        assert!(!output.status.success(), "seed {}", seed);
+    }
+    assert!(ParkMiller::new(MODULUS + 1, 16807).is_ok());
+}
+
+#[test]
+fn unknown_multiplier_is_rejected() {
+    assert!(ParkMiller::new(1, 16808).is_err());
+
+    let output = run_rng(&["--algorithm", "minstd", "--multiplier", "7"]);
+    assert!(!output.status.success());
+
+    let output = run_rng(&["--algorithm", "lcg", "--multiplier", "48271"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!("seed {} bpv {:?}", case.seed, case.bits_per_value),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    for case in cases() {
+        let options = format!("--algorithm park-miller --seed {}", case.seed);
+        assert_cli_output(&options, case.bits_per_value, case.msb_first, &case.bits());
+    }
+}
+
+#[test]
+fn json_reports_parameters() {
+    let output = run_rng(&["--algorithm", "park-miller", "--multiplier", "48271"]);
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["algorithm"].as_str(), Some("park-miller"));
+    assert_eq!(json["initial_state"].as_u64(), Some(1));
+    assert_eq!(json["a"].as_u64(), Some(48271));
+    assert_eq!(json["m"].as_u64(), Some(MODULUS));
+    assert_eq!(json["bits"].as_array().unwrap().len(), 200);
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    assert!(park_miller_bit_stream(100, Some(0), true, 1, 16807).is_err());
+
+    let mut generator = ParkMiller::new(1, 16807).unwrap();
+    let mut bits = Vec::new();
+    assert!(generator.extend_bits(&mut bits, 100, 0, true).is_err());
+}
@@ -0,0 +1,104 @@
+/*
+Zgodność PCG32 z PCG32.py: tests/data/pcg32_python.txt zawiera pierwsze
+10 000 bitów z pcg32_bit_stream dla kilku seedów, seq, bits_per_value
+i kolejności bitów. Plik wygenerowano pythonem z katalogu algorytmy/:
+    pcg32_bit_stream(seed, 10000, bits_per_value=bpv, msb_first=msb)
+gdzie seed to None, initstate albo krotka (initstate, seq).
+bits_per_value = 0 jest błędem, a nie paniką.
+*/
+
+mod common;
+
+use chacha20_rng::pcg32::{DEFAULT_INITSTATE, DEFAULT_SEQ};
+use chacha20_rng::{pcg32_bit_stream, Pcg32};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, optional, optional_arg};
+
+const FIXTURES: &str = include_str!("data/pcg32_python.txt");
+
+struct Case {
+    initstate: Option<u64>,
+    seq: Option<u64>,
+    bits_per_value: usize,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        pcg32_bit_stream(
+            self.n_bits,
+            Some(self.bits_per_value),
+            self.msb_first,
+            self.initstate.unwrap_or(DEFAULT_INITSTATE),
+            self.seq.unwrap_or(DEFAULT_SEQ),
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            initstate: optional(fields[0]),
+            seq: optional(fields[1]),
+            bits_per_value: fields[2].parse().unwrap(),
+            msb_first: fields[3] == "true",
+            n_bits: fields[4].parse().unwrap(),
+            hex: fields[5],
+        })
+        .collect()
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!(
+                "initstate {:?} seq {:?} bpv {} msb {}",
+                case.initstate, case.seq, case.bits_per_value, case.msb_first
+            ),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    for case in cases() {
+        let options = format!(
+            "--algorithm pcg32 {} {}",
+            optional_arg("--seed", case.initstate),
+            optional_arg("--seq", case.seq)
+        );
+        assert_cli_output(
+            &options,
+            Some(case.bits_per_value),
+            case.msb_first,
+            &case.bits(),
+        );
+    }
+}
+
+#[test]
+fn default_seed_is_initstate_one_sequence_one() {
+    let mut default = Pcg32::new(DEFAULT_INITSTATE, DEFAULT_SEQ);
+    let mut seeded: Pcg32 = rand_core::SeedableRng::seed_from_u64(1);
+    for _ in 0..100 {
+        assert_eq!(default.next_u32(), seeded.next_u32());
+    }
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    assert!(pcg32_bit_stream(100, Some(0), true, DEFAULT_INITSTATE, DEFAULT_SEQ).is_err());
+
+    let mut generator = Pcg32::new(DEFAULT_INITSTATE, DEFAULT_SEQ);
+    let mut bits = Vec::new();
+    assert!(generator.extend_bits(&mut bits, 100, 0, true).is_err());
+}
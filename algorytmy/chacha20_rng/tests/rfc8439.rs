@@ -0,0 +1,188 @@
+/*
+Wektory testowe (known-answer) z RFC 8439 dla ChaCha20Generator.
+
+    §2.3.2 -- funkcja bloku: klucz 00..1f, nonce 000000090000004a00000000, licznik 1
+    §2.4.2 -- szyfrowanie: klucz 00..1f, nonce 000000000000004a00000000, licznik 1
+
+Generator zaczyna od licznika 0, więc blok o liczniku 1 to bajty 64..128
+strumienia klucza. Na końcu: set_block_pos, koniec 32-bitowego licznika
+i odrzucenie bits_per_value = 0.
+*/
+
+use chacha20_rng::chacha::{keystream_bytes, KEYSTREAM_EXHAUSTED};
+use chacha20_rng::{chacha20_bit_stream, ChaCha20Generator, Extraction, KEY_LEN, NONCE_LEN};
+use rand_core::RngCore;
+
+const BLOCK_LEN: usize = 64;
+
+fn rfc_key() -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    key
+}
+
+fn hex(text: &str) -> Vec<u8> {
+    let text: String = text.split_whitespace().collect();
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// Generator ustawiony na początek bloku o liczniku 1
+fn generator_at_counter_1(nonce: [u8; NONCE_LEN]) -> ChaCha20Generator {
+    let mut generator = ChaCha20Generator::new(rfc_key(), nonce);
+    let mut skipped = [0u8; BLOCK_LEN];
+    generator.fill_keystream(&mut skipped);
+    generator
+}
+
+const BLOCK_2_3_2: &str = "
+    10 f1 e7 e4 d1 3b 59 15 50 0f dd 1f a3 20 71 c4
+    c7 d1 f4 c7 33 c0 68 03 04 22 aa 9a c3 d4 6c 4e
+    d2 82 64 46 07 9f aa 09 14 c2 d7 05 d9 8b 02 a2
+    b5 12 9c d1 de 16 4e b9 cb d0 83 e8 a2 50 3c 4e";
+
+const NONCE_2_3_2: [u8; NONCE_LEN] = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+
+const PLAINTEXT_2_4_2: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+const CIPHERTEXT_2_4_2: &str = "
+    6e 2e 35 9a 25 68 f9 80 41 ba 07 28 dd 0d 69 81
+    e9 7e 7a ec 1d 43 60 c2 0a 27 af cc fd 9f ae 0b
+    f9 1b 65 c5 52 47 33 ab 8f 59 3d ab cd 62 b3 57
+    16 39 d6 24 e6 51 52 ab 8f 53 0c 35 9f 08 61 d8
+    07 ca 0d bf 50 0d 6a 61 56 a3 8e 08 8a 22 b6 5e
+    52 bc 51 4d 16 cc f8 06 81 8c e9 1a b7 79 37 36
+    5a f9 0b bf 74 a3 5b e6 b4 0b 8e ed f2 78 5e 42
+    87 4d";
+
+const NONCE_2_4_2: [u8; NONCE_LEN] = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+
+#[test]
+fn block_function_matches_section_2_3_2() {
+    let mut generator = generator_at_counter_1(NONCE_2_3_2);
+    let mut block = [0u8; BLOCK_LEN];
+    generator.fill_keystream(&mut block);
+    assert_eq!(block.to_vec(), hex(BLOCK_2_3_2));
+}
+
+#[test]
+fn block_function_matches_section_2_3_2_through_rng_core() {
+    let mut generator = generator_at_counter_1(NONCE_2_3_2);
+    let expected = hex(BLOCK_2_3_2);
+
+    // next_u32 czyta słowa strumienia little-endian
+    for word in expected.chunks_exact(4) {
+        let word = u32::from_le_bytes(word.try_into().unwrap());
+        assert_eq!(generator.next_u32(), word);
+    }
+}
+
+#[test]
+fn whole_block_as_a_single_512_bit_value() {
+    let mut generator = generator_at_counter_1(NONCE_2_3_2);
+    let mut bits = Vec::new();
+    generator
+        .extend_bits(&mut bits, 512, 512, true, Extraction::Truncate)
+        .unwrap();
+
+    let mut expected = Vec::new();
+    for byte in hex(BLOCK_2_3_2) {
+        for i in (0..8).rev() {
+            expected.push((byte >> i) & 1);
+        }
+    }
+    assert_eq!(bits, expected);
+}
+
+#[test]
+fn encryption_keystream_matches_section_2_4_2() {
+    let mut generator = generator_at_counter_1(NONCE_2_4_2);
+    let mut keystream = vec![0u8; PLAINTEXT_2_4_2.len()];
+    generator.fill_bytes(&mut keystream);
+
+    let ciphertext: Vec<u8> = PLAINTEXT_2_4_2
+        .iter()
+        .zip(&keystream)
+        .map(|(p, k)| p ^ k)
+        .collect();
+    assert_eq!(ciphertext, hex(CIPHERTEXT_2_4_2));
+}
+
+#[test]
+fn keystream_is_independent_of_read_sizes() {
+    let mut whole = ChaCha20Generator::new(rfc_key(), NONCE_2_4_2);
+    let mut expected = vec![0u8; 3000];
+    whole.fill_keystream(&mut expected);
+
+    let mut pieces = ChaCha20Generator::new(rfc_key(), NONCE_2_4_2);
+    let mut actual = Vec::new();
+    for size in [1usize, 7, 64, 5, 1024, 999, 900] {
+        let mut chunk = vec![0u8; size];
+        pieces.fill_keystream(&mut chunk);
+        actual.extend(chunk);
+    }
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn set_block_pos_matches_skipped_blocks() {
+    let mut generator = ChaCha20Generator::new(rfc_key(), NONCE_2_3_2);
+    let mut partial = [0u8; 10];
+    generator.fill_keystream(&mut partial);
+    generator.set_block_pos(1);
+    let mut block = [0u8; BLOCK_LEN];
+    generator.fill_keystream(&mut block);
+    assert_eq!(block.to_vec(), hex(BLOCK_2_3_2));
+}
+
+#[test]
+fn try_fill_bytes_reports_end_of_keystream() {
+    // Zostały dwa bloki: 2^32 - 3 i 2^32 - 2
+    let mut generator = ChaCha20Generator::new(rfc_key(), NONCE_2_4_2);
+    generator.set_block_pos(u32::MAX - 2);
+
+    // Za długie żądanie nie zużywa strumienia
+    let mut too_long = [0u8; 2 * BLOCK_LEN + 1];
+    let err = generator.try_fill_bytes(&mut too_long).unwrap_err();
+    assert_eq!(err.code().map(|code| code.get()), Some(KEYSTREAM_EXHAUSTED));
+
+    let mut rest = [0u8; 2 * BLOCK_LEN];
+    generator.try_fill_bytes(&mut rest).unwrap();
+    let mut expected = ChaCha20Generator::new(rfc_key(), NONCE_2_4_2);
+    expected.set_block_pos(u32::MAX - 2);
+    let mut first = [0u8; BLOCK_LEN];
+    expected.fill_keystream(&mut first);
+    assert_eq!(rest[..BLOCK_LEN], first);
+
+    assert!(generator.try_fill_bytes(&mut [0u8; 1]).is_err());
+    assert!(generator.try_fill_bytes(&mut []).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "wyczerpany")]
+fn fill_bytes_panics_at_end_of_keystream() {
+    let mut generator = ChaCha20Generator::new(rfc_key(), NONCE_2_4_2);
+    generator.set_block_pos(u32::MAX - 1);
+    generator.fill_bytes(&mut [0u8; BLOCK_LEN + 1]);
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    let nonce = [0u8; NONCE_LEN];
+    assert!(chacha20_bit_stream(100, Some(0), true, &rfc_key(), &nonce).is_err());
+    assert!(chacha20_bit_stream(0, Some(0), true, &rfc_key(), &nonce).is_err());
+
+    let mut generator = ChaCha20Generator::new(rfc_key(), nonce);
+    let mut bits = Vec::new();
+    assert!(generator
+        .extend_bits(&mut bits, 100, 0, true, Extraction::Truncate)
+        .is_err());
+    assert!(bits.is_empty());
+
+    assert!(keystream_bytes(100, 0, Extraction::Contiguous).is_err());
+    assert_eq!(keystream_bytes(100, 7, Extraction::Truncate), Ok(15));
+}
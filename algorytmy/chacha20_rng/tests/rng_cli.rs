@@ -0,0 +1,87 @@
+/*
+Binarka rng: każdy zarejestrowany algorytm (poza nieodtwarzalnym system) daje
+ten sam wynik co chacha20_rng z tymi samymi argumentami, a JSON podaje nazwę
+algorytmu.
+*/
+
+use chacha20_rng::Algorithm;
+use serde_json::Value;
+use std::process::{Command, Output};
+
+fn run(exe: &str, args: &[&str]) -> Output {
+    Command::new(exe).args(args).output().unwrap()
+}
+
+#[test]
+fn rng_matches_chacha20_rng_for_every_algorithm() {
+    // Entropia systemu jest nieodtwarzalna — porównywalne są tylko generatory z seedem
+    for algorithm in Algorithm::ALL
+        .iter()
+        .filter(|&&algorithm| algorithm != Algorithm::System)
+    {
+        let args = [
+            "--algorithm",
+            algorithm.name(),
+            "--seed",
+            "42",
+            "--bits",
+            "4096",
+            "--format",
+            "hex",
+        ];
+        let rng = run(env!("CARGO_BIN_EXE_rng"), &args);
+        let chacha = run(env!("CARGO_BIN_EXE_chacha20_rng"), &args);
+        assert!(rng.status.success(), "{}", algorithm);
+        assert_eq!(rng.stdout, chacha.stdout, "{}", algorithm);
+    }
+}
+
+#[test]
+fn json_reports_algorithm_and_default_bits_per_value() {
+    for algorithm in Algorithm::ALL {
+        let output = run(
+            env!("CARGO_BIN_EXE_rng"),
+            &["--algorithm", algorithm.name(), "--bits", "256"],
+        );
+        assert!(output.status.success(), "{}", algorithm);
+        let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["algorithm"].as_str(), Some(algorithm.name()));
+        assert_eq!(json["bits"].as_array().unwrap().len(), 256);
+        assert!(json["time"].is_number());
+    }
+}
+
+#[test]
+fn positional_form_defaults_to_chacha20() {
+    let rng = run(env!("CARGO_BIN_EXE_rng"), &["42", "1000", "12", "false"]);
+    let chacha = run(
+        env!("CARGO_BIN_EXE_chacha20_rng"),
+        &[
+            "--seed",
+            "42",
+            "--bits",
+            "1000",
+            "--bits-per-value",
+            "12",
+            "--lsb-first",
+        ],
+    );
+    let rng: Value = serde_json::from_slice(&rng.stdout).unwrap();
+    let chacha: Value = serde_json::from_slice(&chacha.stdout).unwrap();
+    assert_eq!(rng["algorithm"].as_str(), Some("chacha20"));
+    assert_eq!(rng["bits"], chacha["bits"]);
+}
+
+#[test]
+fn unknown_algorithm_lists_available_ones() {
+    let output = run(env!("CARGO_BIN_EXE_rng"), &["--algorithm", "rc4"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for algorithm in Algorithm::ALL {
+        assert!(stderr.contains(algorithm.name()), "{}", stderr);
+    }
+    // Tylko błąd i wskazówka, bez całej pomocy
+    assert!(stderr.contains("rng --help"), "{}", stderr);
+    assert!(!stderr.contains("rng [opcje]"), "{}", stderr);
+    assert!(stderr.lines().count() <= 3, "{}", stderr);
+}
@@ -0,0 +1,96 @@
+/*
+Zgodność SplitMix64 z SplitMix64.py: tests/data/splitmix64_python.txt zawiera
+pierwsze 10 000 bitów z splitmix64_bit_stream dla kilku seedów,
+bits_per_value i kolejności bitów. Plik wygenerowano pythonem z katalogu algorytmy/:
+    splitmix64_bit_stream(seed, 10000, bits_per_value=bpv, msb_first=msb)
+bits_per_value = 0 jest błędem, a nie paniką.
+*/
+
+mod common;
+
+use chacha20_rng::splitmix64::DEFAULT_SEED;
+use chacha20_rng::{derive_key, splitmix64_bit_stream, SplitMix64};
+use common::{assert_cli_output, assert_fixture_hex, fixture_rows, optional, optional_arg};
+
+const FIXTURES: &str = include_str!("data/splitmix64_python.txt");
+
+struct Case {
+    seed: Option<u64>,
+    bits_per_value: Option<usize>,
+    msb_first: bool,
+    n_bits: usize,
+    hex: &'static str,
+}
+
+impl Case {
+    fn bits(&self) -> Vec<u8> {
+        splitmix64_bit_stream(
+            self.n_bits,
+            self.bits_per_value,
+            self.msb_first,
+            self.seed.unwrap_or(DEFAULT_SEED),
+        )
+        .unwrap()
+        .0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    fixture_rows(FIXTURES)
+        .map(|fields| Case {
+            seed: optional(fields[0]),
+            bits_per_value: optional(fields[1]),
+            msb_first: fields[2] == "true",
+            n_bits: fields[3].parse().unwrap(),
+            hex: fields[4],
+        })
+        .collect()
+}
+
+#[test]
+fn bit_stream_matches_python_reference() {
+    let cases = cases();
+    assert!(!cases.is_empty());
+
+    for case in cases {
+        assert_fixture_hex(
+            &case.bits(),
+            case.hex,
+            &format!(
+                "seed {:?} bpv {:?} msb {}",
+                case.seed, case.bits_per_value, case.msb_first
+            ),
+        );
+    }
+}
+
+#[test]
+fn cli_output_matches_python_reference() {
+    for case in cases() {
+        // Bez --seed i --bits-per-value CLI ma użyć domyślnych z Pythona
+        let options = format!(
+            "--algorithm splitmix64 {}",
+            optional_arg("--seed", case.seed)
+        );
+        assert_cli_output(&options, case.bits_per_value, case.msb_first, &case.bits());
+    }
+}
+
+#[test]
+fn chacha20_key_is_first_four_outputs() {
+    let mut generator = SplitMix64::new(42);
+    let mut expected = Vec::new();
+    for _ in 0..4 {
+        expected.extend_from_slice(&generator.next_u64().to_le_bytes());
+    }
+    assert_eq!(derive_key(42).to_vec(), expected);
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    assert!(splitmix64_bit_stream(100, Some(0), true, DEFAULT_SEED).is_err());
+
+    let mut generator = SplitMix64::new(DEFAULT_SEED);
+    let mut bits = Vec::new();
+    assert!(generator.extend_bits(&mut bits, 100, 0, true).is_err());
+}
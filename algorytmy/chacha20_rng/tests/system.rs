@@ -0,0 +1,141 @@
+/*
+Systemowy CSPRNG: oba źródła (getrandom(2) i /dev/urandom) dają niezależne
+bajty, ekstrakcja bitów działa jak w ChaCha20 (truncate i contiguous), a JSON
+z CLI jasno zgłasza, że seed jest ignorowany. Wyniku nie da się porównać
+z Pythonem, więc sprawdzana jest tylko struktura i zgrubna równowaga bitów.
+*/
+
+use chacha20_rng::system::{EntropySource, SystemRng};
+use chacha20_rng::{system_bit_stream, Extraction};
+use serde_json::Value;
+use std::process::Command;
+
+fn sources() -> Vec<EntropySource> {
+    let mut sources = vec![EntropySource::DevUrandom];
+    if cfg!(target_os = "linux") {
+        sources.push(EntropySource::GetRandom);
+    }
+    sources
+}
+
+#[test]
+fn every_source_produces_fresh_bytes() {
+    for source in sources() {
+        let mut generator = SystemRng::with_source(source).unwrap();
+        assert_eq!(generator.source(), source);
+
+        // Dwa bloki po 64 bajty są równe z prawdopodobieństwem 2^-512;
+        // 5000 bajtów przekracza bufor, więc sprawdza też jego uzupełnianie
+        let mut first = vec![0u8; 64];
+        let mut second = vec![0u8; 5000];
+        generator.fill_bytes(&mut first).unwrap();
+        generator.fill_bytes(&mut second).unwrap();
+        assert_ne!(first, second[..64], "{}", source);
+        assert!(second.iter().any(|&byte| byte != 0), "{}", source);
+    }
+}
+
+#[test]
+fn new_prefers_getrandom_on_linux() {
+    let generator = SystemRng::new().unwrap();
+    if cfg!(target_os = "linux") {
+        assert_eq!(generator.source(), EntropySource::GetRandom);
+    }
+}
+
+#[test]
+fn extraction_modes_give_requested_widths() {
+    for source in sources() {
+        let mut generator = SystemRng::with_source(source).unwrap();
+        for extraction in [Extraction::Truncate, Extraction::Contiguous] {
+            for bits_per_value in [1, 7, 12, 32, 64, 100] {
+                let mut out = Vec::new();
+                generator
+                    .extend_bits(&mut out, 1001, bits_per_value, true, extraction)
+                    .unwrap();
+                assert_eq!(out.len(), 1001);
+                assert!(out.iter().all(|&bit| bit <= 1));
+            }
+        }
+    }
+
+    // Truncate z bpv = 12: z każdych dwóch bajtów odrzucane są 4 najstarsze bity
+    let (bits, _) = system_bit_stream(1200, Some(12), true).unwrap();
+    assert_eq!(bits.len(), 1200);
+}
+
+#[test]
+fn bits_are_roughly_balanced() {
+    // Dla 10^6 uczciwych bitów odchylenie standardowe liczby jedynek to 500
+    let n_bits = 1_000_000;
+    let (bits, _) = system_bit_stream(n_bits, None, true).unwrap();
+    let ones = bits.iter().filter(|&&bit| bit == 1).count() as i64;
+    assert!((ones - n_bits as i64 / 2).abs() < 3000, "jedynek: {}", ones);
+}
+
+#[test]
+fn json_reports_that_seed_is_ignored() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(["--algorithm", "system", "--bits", "64"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["algorithm"].as_str(), Some("system"));
+    assert_eq!(json["seed_ignored"].as_bool(), Some(true));
+    assert!(json["seed"].is_null());
+    assert!(json.get("warnings").is_none());
+    assert_eq!(json["bits"].as_array().unwrap().len(), 64);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(["--algorithm", "system", "--seed", "42", "--bits", "64"])
+        .output()
+        .unwrap();
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["seed"].as_u64(), Some(42));
+    assert_eq!(json["seed_ignored"].as_bool(), Some(true));
+    assert_eq!(json["warnings"].as_array().unwrap().len(), 1);
+
+    // Format bez metadanych: ostrzeżenie trafia na stderr
+    let output = Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args(["--algorithm", "system", "--seed", "42", "--format", "hex"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("ignorowany"));
+}
+
+#[test]
+fn chacha_only_options_are_rejected() {
+    for args in [["--key", &"00".repeat(32)], ["--nonce", &"00".repeat(12)]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_rng"))
+            .args(["--algorithm", "system"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(!output.status.success(), "{:?}", args);
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args([
+            "--algorithm",
+            "system",
+            "--extraction",
+            "contiguous",
+            "--bits-per-value",
+            "12",
+            "--format",
+            "ascii01",
+            "--width",
+            "0",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end().len(),
+        200
+    );
+}
@@ -0,0 +1,118 @@
+/*
+Xoshiro256**: wektory referencyjnej implementacji w C (xoshiro256starstar.c
+dla stanu [1, 2, 3, 4]) oraz zgodność seedowania, jump i long_jump
+z rand_xoshiro::Xoshiro256StarStar. bits_per_value = 0 jest błędem, a nie paniką.
+*/
+
+use chacha20_rng::output::{pack_bits, to_hex};
+use chacha20_rng::{xoshiro256_bit_stream, Xoshiro256StarStar};
+use rand_core::{RngCore, SeedableRng};
+use std::process::Command;
+
+#[test]
+fn reference_vector_for_state_1_2_3_4() {
+    let expected: [u64; 10] = [
+        11520,
+        0,
+        1509978240,
+        1215971899390074240,
+        1216172134540287360,
+        607988272756665600,
+        16172922978634559625,
+        8476171486693032832,
+        10595114339597558777,
+        2904607092377533576,
+    ];
+    let mut generator = Xoshiro256StarStar::from_state([1, 2, 3, 4]);
+    for value in expected {
+        assert_eq!(generator.next_u64(), value);
+    }
+}
+
+#[test]
+fn seed_from_u64_matches_rand_xoshiro() {
+    for seed in [0, 1, 42, 123456789, u64::MAX] {
+        let mut ours = Xoshiro256StarStar::seed_from_u64(seed);
+        let mut reference = rand_xoshiro::Xoshiro256StarStar::seed_from_u64(seed);
+        for _ in 0..1000 {
+            assert_eq!(ours.next_u64(), reference.next_u64(), "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn jump_and_long_jump_match_rand_xoshiro() {
+    let mut ours = Xoshiro256StarStar::new(42);
+    let mut reference = rand_xoshiro::Xoshiro256StarStar::seed_from_u64(42);
+
+    ours.jump();
+    reference.jump();
+    for _ in 0..100 {
+        assert_eq!(ours.next_u64(), reference.next_u64());
+    }
+
+    ours.long_jump();
+    reference.long_jump();
+    for _ in 0..100 {
+        assert_eq!(ours.next_u64(), reference.next_u64());
+    }
+}
+
+#[test]
+fn cli_applies_jumps_before_generating() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rng"))
+        .args([
+            "--algorithm",
+            "xoshiro256",
+            "--seed",
+            "7",
+            "--long-jump",
+            "1",
+            "--jump",
+            "2",
+            "--bits",
+            "1000",
+            "--bits-per-value",
+            "40",
+            "--format",
+            "hex",
+            "--width",
+            "0",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let mut generator = Xoshiro256StarStar::new(7);
+    generator.long_jump();
+    generator.jump();
+    generator.jump();
+    let mut bits = Vec::new();
+    generator.extend_bits(&mut bits, 1000, 40, true).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        to_hex(&pack_bits(&bits, true))
+    );
+}
+
+#[test]
+fn bit_stream_takes_low_bits_of_each_value() {
+    let (bits, _) = xoshiro256_bit_stream(64 * 3, Some(64), true, 5).unwrap();
+    let (low, _) = xoshiro256_bit_stream(16 * 3, Some(16), true, 5).unwrap();
+    for value in 0..3 {
+        assert_eq!(
+            &bits[64 * value + 48..64 * (value + 1)],
+            &low[16 * value..16 * (value + 1)]
+        );
+    }
+}
+
+#[test]
+fn zero_bits_per_value_is_an_error() {
+    assert!(xoshiro256_bit_stream(100, Some(0), true, 5).is_err());
+
+    let mut generator = Xoshiro256StarStar::new(5);
+    let mut bits = Vec::new();
+    assert!(generator.extend_bits(&mut bits, 100, 0, true).is_err());
+}